@@ -0,0 +1,4 @@
+ALTER TABLE validation_logs ADD COLUMN IF NOT EXISTS counterpart_vat VARCHAR(20);
+ALTER TABLE validation_logs ADD COLUMN IF NOT EXISTS vat_categories TEXT[];
+
+CREATE INDEX IF NOT EXISTS idx_issuer_counterpart ON validation_logs(issuer_vat, counterpart_vat);
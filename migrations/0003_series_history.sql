@@ -0,0 +1,4 @@
+ALTER TABLE validation_logs ADD COLUMN IF NOT EXISTS content_hash VARCHAR(64);
+ALTER TABLE validation_logs ADD COLUMN IF NOT EXISTS issue_date DATE;
+
+CREATE INDEX IF NOT EXISTS idx_issuer_series ON validation_logs(issuer_vat, invoice_series, invoice_aa);
@@ -0,0 +1,5 @@
+ALTER TABLE validation_logs ADD COLUMN IF NOT EXISTS mark VARCHAR(20);
+ALTER TABLE validation_logs ADD COLUMN IF NOT EXISTS total_net_amount NUMERIC(15, 2);
+ALTER TABLE validation_logs ADD COLUMN IF NOT EXISTS total_vat_amount NUMERIC(15, 2);
+
+CREATE INDEX IF NOT EXISTS idx_mark ON validation_logs(mark);
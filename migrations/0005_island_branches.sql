@@ -0,0 +1,12 @@
+-- Branches located on the Aegean islands that may use the reduced VAT rates (17%, 9%, 4%)
+CREATE TABLE IF NOT EXISTS island_branches (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    vat_number VARCHAR(20) NOT NULL,
+    branch INTEGER NOT NULL,
+    island VARCHAR(100) NOT NULL,
+    valid_from DATE NOT NULL,
+    valid_until DATE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_island_branches_vat ON island_branches(vat_number, branch);
@@ -0,0 +1,4 @@
+-- Normalized invoice of each validation, so a new upload can be diffed against the previous attempt
+ALTER TABLE validation_logs ADD COLUMN IF NOT EXISTS invoice_json JSONB;
+
+CREATE INDEX IF NOT EXISTS idx_invoice_identity ON validation_logs(issuer_vat, invoice_series, invoice_aa, created_at DESC);
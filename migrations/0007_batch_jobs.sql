@@ -0,0 +1,25 @@
+-- Asynchronous batch validation; the uploaded files are kept so a job resumes after a restart
+CREATE TABLE IF NOT EXISTS batch_jobs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    status VARCHAR(20) NOT NULL DEFAULT 'queued', -- queued, running, completed, cancelled, failed
+    options JSONB NOT NULL,                       -- language and flags of the submitting request
+    total_files INT NOT NULL,
+    processed_files INT NOT NULL DEFAULT 0,
+    cancel_requested BOOLEAN NOT NULL DEFAULT FALSE,
+    error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    started_at TIMESTAMP WITH TIME ZONE,
+    finished_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE TABLE IF NOT EXISTS batch_job_files (
+    job_id UUID NOT NULL REFERENCES batch_jobs(id) ON DELETE CASCADE,
+    position INT NOT NULL,
+    filename TEXT NOT NULL,
+    content TEXT NOT NULL,
+    result_json JSONB, -- NULL until the file is processed
+    processed_at TIMESTAMP WITH TIME ZONE,
+    PRIMARY KEY (job_id, position)
+);
+
+CREATE INDEX IF NOT EXISTS idx_batch_jobs_status ON batch_jobs(status);
@@ -0,0 +1,2 @@
+-- Files of a job extracted from an uploaded archive keep the archive's name; `filename` is then the path inside it
+ALTER TABLE batch_job_files ADD COLUMN IF NOT EXISTS archive TEXT;
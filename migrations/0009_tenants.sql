@@ -0,0 +1,25 @@
+-- Client companies sharing the validator; each one only sees its own validation history
+CREATE TABLE IF NOT EXISTS tenants (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS api_keys (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    key_hash CHAR(64) NOT NULL UNIQUE, -- SHA-256 of the key; the key itself is only shown when issued
+    key_prefix VARCHAR(16) NOT NULL,   -- Start of the key, to tell a tenant's keys apart
+    name TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    last_used_at TIMESTAMP WITH TIME ZONE,
+    revoked_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_keys_tenant ON api_keys(tenant_id);
+
+-- Rows logged before tenants existed keep a NULL tenant and are no longer part of anyone's history
+ALTER TABLE validation_logs ADD COLUMN IF NOT EXISTS tenant_id UUID REFERENCES tenants(id);
+ALTER TABLE batch_jobs ADD COLUMN IF NOT EXISTS tenant_id UUID REFERENCES tenants(id);
+
+CREATE INDEX IF NOT EXISTS idx_validation_logs_tenant ON validation_logs(tenant_id, issuer_vat);
@@ -0,0 +1,4 @@
+-- The instance running a job and when it last reported, so several instances never run the same job
+-- and one that died is taken over once its heartbeat is stale
+ALTER TABLE batch_jobs ADD COLUMN IF NOT EXISTS owner UUID;
+ALTER TABLE batch_jobs ADD COLUMN IF NOT EXISTS heartbeat_at TIMESTAMP WITH TIME ZONE;
@@ -0,0 +1,144 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use serde::Deserialize;
+use sqlx::types::Uuid;
+use crate::persistence::{island_registry, tenants};
+use crate::state::AppState;
+use crate::validation::island_rules::IslandBranch;
+use super::auth::{generate_key, hash_key, key_prefix};
+use super::{error_response, internal_error};
+
+#[derive(Deserialize)]
+pub struct NewTenant {
+    name: String, // The client company
+}
+
+#[derive(Deserialize)]
+pub struct BranchFilter {
+    #[serde(default)]
+    vat_number: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+pub struct NewKey {
+    #[serde(default)]
+    name: Option<String>, // What the key is for, e.g. "ERP export"
+}
+
+pub async fn create_tenant(State(state): State<AppState>, Json(body): Json<NewTenant>) -> Response {
+    let name = body.name.trim();
+    if name.is_empty() {
+        return error_response(StatusCode::BAD_REQUEST, "Invalid Tenant", "name is required");
+    }
+
+    match tenants::create_tenant(&state.db, name).await {
+        Ok(tenant) => (StatusCode::CREATED, Json(tenant)).into_response(),
+        Err(e) => internal_error("Tenant Not Created", e),
+    }
+}
+
+pub async fn list_tenants(State(state): State<AppState>) -> Response {
+    match tenants::list_tenants(&state.db).await {
+        Ok(list) => Json(list).into_response(),
+        Err(e) => internal_error("Tenants Not Loaded", e),
+    }
+}
+
+/// Issues a key for the tenant; the response is the only place the key is ever shown
+pub async fn issue_key(State(state): State<AppState>, Path(tenant_id): Path<String>, body: Option<Json<NewKey>>) -> Response {
+    let tenant_id = match Uuid::parse_str(&tenant_id) {
+        Ok(id) => id,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, "Invalid Tenant Id", e),
+    };
+    match tenants::tenant_exists(&state.db, tenant_id).await {
+        Ok(true) => {}
+        Ok(false) => return error_response(StatusCode::NOT_FOUND, "Tenant Not Found", tenant_id),
+        Err(e) => return internal_error("Key Not Issued", e),
+    }
+
+    let name = body.and_then(|Json(b)| b.name).map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+    let key = generate_key();
+    match tenants::store_key(&state.db, tenant_id, name.as_deref(), &hash_key(&key), key_prefix(&key)).await {
+        Ok(id) => (StatusCode::CREATED, Json(serde_json::json!({
+            "id": id.to_string(),
+            "tenant_id": tenant_id.to_string(),
+            "name": name,
+            "key": key,
+            "key_prefix": key_prefix(&key)
+        }))).into_response(),
+        Err(e) => internal_error("Key Not Issued", e),
+    }
+}
+
+pub async fn list_keys(State(state): State<AppState>, Path(tenant_id): Path<String>) -> Response {
+    let tenant_id = match Uuid::parse_str(&tenant_id) {
+        Ok(id) => id,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, "Invalid Tenant Id", e),
+    };
+
+    match tenants::list_keys(&state.db, tenant_id).await {
+        Ok(keys) => Json(keys).into_response(),
+        Err(e) => internal_error("Keys Not Loaded", e),
+    }
+}
+
+/// Takes effect on the key's next request; revoking twice keeps the first time
+pub async fn revoke_key(State(state): State<AppState>, Path(key_id): Path<String>) -> Response {
+    let key_id = match Uuid::parse_str(&key_id) {
+        Ok(id) => id,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, "Invalid Key Id", e),
+    };
+
+    match tenants::revoke_key(&state.db, key_id).await {
+        Ok(Some(revoked_at)) => Json(serde_json::json!({
+            "id": key_id.to_string(),
+            "revoked_at": revoked_at
+        })).into_response(),
+        Ok(None) => error_response(StatusCode::NOT_FOUND, "Key Not Found", key_id),
+        Err(e) => internal_error("Key Not Revoked", e),
+    }
+}
+
+pub async fn list_island_branches(State(state): State<AppState>, Query(filter): Query<BranchFilter>) -> Response {
+    match island_registry::list_branches(&state.db, filter.vat_number.as_deref()).await {
+        Ok(branches) => Json(branches).into_response(),
+        Err(e) => internal_error("Island Branches Not Loaded", e),
+    }
+}
+
+/// Loads island registrations, e.g. exported from the tax office's list; all of them are stored or none
+pub async fn add_island_branches(State(state): State<AppState>, Json(mut branches): Json<Vec<IslandBranch>>) -> Response {
+    if branches.is_empty() {
+        return error_response(StatusCode::BAD_REQUEST, "Invalid Island Branches", "No branches given");
+    }
+    for (idx, branch) in branches.iter_mut().enumerate() {
+        branch.vat_number = branch.vat_number.trim().to_string();
+        branch.island = branch.island.trim().to_string();
+        if let Some(reason) = branch.registration_error() {
+            return error_response(StatusCode::BAD_REQUEST, "Invalid Island Branches", format!("entry {}: {}", idx + 1, reason));
+        }
+    }
+
+    match island_registry::add_branches(&state.db, &state.island_registry, &branches).await {
+        Ok(ids) => (StatusCode::CREATED, Json(serde_json::json!({
+            "ids": ids.iter().map(|id| id.to_string()).collect::<Vec<_>>()
+        }))).into_response(),
+        Err(e) => internal_error("Island Branches Not Stored", e),
+    }
+}
+
+pub async fn delete_island_branch(State(state): State<AppState>, Path(id): Path<String>) -> Response {
+    let id = match Uuid::parse_str(&id) {
+        Ok(id) => id,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, "Invalid Island Branch Id", e),
+    };
+
+    match island_registry::delete_branch(&state.db, &state.island_registry, id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => error_response(StatusCode::NOT_FOUND, "Island Branch Not Found", id),
+        Err(e) => internal_error("Island Branch Not Deleted", e),
+    }
+}
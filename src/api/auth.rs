@@ -0,0 +1,85 @@
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sqlx::types::Uuid;
+use crate::persistence::tenants;
+use crate::state::AppState;
+use crate::utils::hash;
+use super::{error_response, internal_error};
+
+const KEY_PREFIX: &str = "aade_";
+const DISPLAY_PREFIX_LEN: usize = 12;
+
+/// The caller's tenant, added to the request by `require_api_key`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TenantId(pub Uuid);
+
+/// A new key: 32 random bytes, hex encoded behind a recognizable prefix
+pub fn generate_key() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    format!("{}{}", KEY_PREFIX, hex::encode(bytes))
+}
+
+// Keys are long and random, a plain SHA-256 is enough to keep them out of the database
+pub fn hash_key(key: &str) -> String {
+    hash::calculate_hash(key)
+}
+
+/// What admins see of a key once it has been issued
+pub fn key_prefix(key: &str) -> &str {
+    key.get(..DISPLAY_PREFIX_LEN).unwrap_or(key)
+}
+
+/// `Authorization: Bearer <key>`, or `X-API-Key: <key>` for clients that cannot set Authorization
+pub fn presented_key(headers: &HeaderMap) -> Option<&str> {
+    let bearer = headers.get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    bearer
+        .or_else(|| headers.get("x-api-key").and_then(|v| v.to_str().ok()))
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+}
+
+fn unauthorized(details: &str) -> Response {
+    let mut response = error_response(StatusCode::UNAUTHORIZED, "Unauthorized", details);
+    response.headers_mut().insert(header::WWW_AUTHENTICATE, header::HeaderValue::from_static("Bearer"));
+    response
+}
+
+/// Lets the request through with its tenant when it carries a live API key
+pub async fn require_api_key(State(state): State<AppState>, mut request: Request, next: Next) -> Response {
+    let Some(key_hash) = presented_key(request.headers()).map(hash_key) else {
+        return unauthorized("Missing API key");
+    };
+
+    match tenants::authenticate(&state.db, &key_hash).await {
+        Ok(Some(tenant_id)) => {
+            request.extensions_mut().insert(TenantId(tenant_id));
+            next.run(request).await
+        }
+        Ok(None) => unauthorized("Unknown or revoked API key"),
+        Err(e) => internal_error("Authentication Failed", e),
+    }
+}
+
+/// The /admin endpoints answer to ADMIN_API_KEY only, not to tenant keys
+pub async fn require_admin_key(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let Some(admin_key_hash) = state.admin_key_hash.as_deref() else {
+        return error_response(StatusCode::FORBIDDEN, "Forbidden", "ADMIN_API_KEY is not configured");
+    };
+
+    // Hashes have a fixed length, so comparing them says nothing about the key
+    match presented_key(request.headers()).map(hash_key) {
+        Some(key_hash) if key_hash == admin_key_hash => next.run(request).await,
+        Some(_) => unauthorized("Invalid admin key"),
+        None => unauthorized("Missing API key"),
+    }
+}
+
@@ -0,0 +1,88 @@
+use axum::{
+    body::Body,
+    http::{header, HeaderMap},
+    response::{sse::{Event, KeepAlive, Sse}, IntoResponse, Response},
+};
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tokio_stream::{wrappers::ReceiverStream, StreamExt};
+use crate::state::AppState;
+use super::auth::TenantId;
+use super::batch_summary::{BatchSummary, BatchTally};
+use super::validate::{validate_upload, BatchFileResult, BatchUpload, ValidateOptions};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamFormat {
+    Ndjson,      // One JSON event per line
+    EventStream, // Server-Sent Events, the event name repeats the `event` field
+}
+
+impl StreamFormat {
+    /// Chosen by the Accept header; without one of these the batch is answered as a single JSON array
+    pub fn negotiate(headers: &HeaderMap) -> Option<Self> {
+        let accept = headers.get(header::ACCEPT)?.to_str().ok()?;
+        if accept.contains("text/event-stream") {
+            Some(StreamFormat::EventStream)
+        } else if accept.contains("application/x-ndjson") {
+            Some(StreamFormat::Ndjson)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum BatchEvent {
+    Result(Box<BatchFileResult>), // Sent as soon as the file is validated
+    Progress { processed: usize, total: usize },
+    Summary(Box<BatchSummary>), // Last event of the stream
+}
+
+impl BatchEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BatchEvent::Result(_) => "result",
+            BatchEvent::Progress { .. } => "progress",
+            BatchEvent::Summary(_) => "summary",
+        }
+    }
+}
+
+/// Validates the files one by one, sending each result while the rest are still pending
+pub(crate) fn stream_batch(state: AppState, tenant: TenantId, uploads: Vec<BatchUpload>, options: ValidateOptions, format: StreamFormat) -> Response {
+    let (tx, rx) = mpsc::channel(16);
+
+    tokio::spawn(async move {
+        let total = uploads.len();
+        let mut tally = BatchTally::default();
+
+        for (i, upload) in uploads.into_iter().enumerate() {
+            let result = validate_upload(&state, tenant, upload, &options).await;
+            tally.record(&result);
+            // The client went away, the remaining files are not validated
+            if tx.send(BatchEvent::Result(Box::new(result))).await.is_err()
+                || tx.send(BatchEvent::Progress { processed: i + 1, total }).await.is_err()
+            {
+                return;
+            }
+        }
+        let _ = tx.send(BatchEvent::Summary(Box::new(tally.summary()))).await;
+    });
+
+    let events = ReceiverStream::new(rx);
+    match format {
+        StreamFormat::Ndjson => {
+            let lines = events.map(|event| {
+                let mut line = serde_json::to_vec(&event)?;
+                line.push(b'\n');
+                Ok::<_, serde_json::Error>(line)
+            });
+            ([(header::CONTENT_TYPE, "application/x-ndjson")], Body::from_stream(lines)).into_response()
+        }
+        StreamFormat::EventStream => {
+            let events = events.map(|event| Event::default().event(event.name()).json_data(&event));
+            Sse::new(events).keep_alive(KeepAlive::default()).into_response()
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use crate::domain::enums::{InvoiceType, VatCategory};
+use crate::domain::invoice::Invoice;
+use crate::validation::result::{Severity, ValidationStatus};
+use super::validate::{BatchFileResult, FileStatus};
+
+/// How many error codes the summary lists
+const TOP_ERROR_CODES: usize = 10;
+
+/// The amounts of one invoice, kept next to its report so a batch can be totalled without the XML
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvoiceAmounts {
+    pub invoice_type: InvoiceType,
+    pub net: Decimal,
+    pub vat: Decimal,
+    pub gross: Decimal,
+    pub by_vat_category: Vec<CategoryAmounts>, // Summed from the lines, in category order
+}
+
+impl InvoiceAmounts {
+    pub fn from_invoice(invoice: &Invoice) -> Self {
+        let by_vat_category = VatCategory::ALL.into_iter()
+            .filter_map(|vat_category| {
+                let mut lines = invoice.lines.iter().filter(|l| l.vat_category == vat_category).peekable();
+                lines.peek()?;
+                let mut amounts = Amounts::default();
+                for line in lines {
+                    amounts.add(line.net_value, line.vat_amount, line.net_value + line.vat_amount);
+                }
+                Some(CategoryAmounts { vat_category, amounts })
+            })
+            .collect();
+
+        Self {
+            invoice_type: invoice.header.invoice_type,
+            net: invoice.totals.total_net_amount,
+            vat: invoice.totals.total_vat_amount,
+            gross: invoice.totals.total_gross_amount,
+            by_vat_category,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Amounts {
+    pub net: Decimal,
+    pub vat: Decimal,
+    pub gross: Decimal,
+}
+
+impl Amounts {
+    fn add(&mut self, net: Decimal, vat: Decimal, gross: Decimal) {
+        self.net += net;
+        self.vat += vat;
+        self.gross += gross;
+    }
+
+    // Credit notes lower the totals whether their amounts are written positive or negative
+    fn record(&mut self, invoice_type: InvoiceType, net: Decimal, vat: Decimal, gross: Decimal) {
+        if invoice_type.is_credit_note() {
+            self.add(-net.abs(), -vat.abs(), -gross.abs());
+        } else {
+            self.add(net, vat, gross);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryAmounts {
+    pub vat_category: VatCategory,
+    #[serde(flatten)]
+    pub amounts: Amounts,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypeAmounts {
+    pub invoice_type: InvoiceType,
+    pub invoices: usize,
+    #[serde(flatten)]
+    pub amounts: Amounts,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CodeCount {
+    pub code: String,
+    pub count: usize,
+}
+
+/// Roll-up of a batch. Amounts cover the valid (Green / Yellow) invoices only; credit notes
+/// (see `InvoiceType::is_credit_note`) are subtracted, so the totals are what the batch nets to
+/// and the `by_invoice_type` rows add up to them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatchSummary {
+    pub files: usize,
+    pub failed_files: usize, // Unreadable or not parseable
+    pub invoices: usize,
+    pub green: usize,
+    pub yellow: usize,
+    pub red: usize,
+    pub top_error_codes: Vec<CodeCount>, // Most frequent first
+    pub totals: Amounts,
+    pub by_vat_category: Vec<CategoryAmounts>,
+    pub by_invoice_type: Vec<TypeAmounts>,
+}
+
+impl BatchSummary {
+    pub fn from_results<'a>(results: impl IntoIterator<Item = &'a BatchFileResult>) -> Self {
+        let mut tally = BatchTally::default();
+        for result in results {
+            tally.record(result);
+        }
+        tally.summary()
+    }
+
+    /// One row per figure: `section,key,count,net,vat,gross`
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("section,key,count,net,vat,gross\n");
+        let mut count = |section: &str, key: &str, count: usize| {
+            let _ = writeln!(csv, "{},{},{},,,", section, key, count);
+        };
+        count("files", "total", self.files);
+        count("files", "failed", self.failed_files);
+        count("invoices", "total", self.invoices);
+        count("status", "green", self.green);
+        count("status", "yellow", self.yellow);
+        count("status", "red", self.red);
+        for code in &self.top_error_codes {
+            count("error_code", &code.code, code.count);
+        }
+
+        let valid = self.green + self.yellow;
+        let mut amounts = |section: &str, key: &str, count: Option<usize>, amounts: &Amounts| {
+            let count = count.map(|c| c.to_string()).unwrap_or_default();
+            let _ = writeln!(csv, "{},{},{},{},{},{}", section, key, count, amounts.net, amounts.vat, amounts.gross);
+        };
+        amounts("totals", "valid", Some(valid), &self.totals);
+        for category in &self.by_vat_category {
+            amounts("vat_category", &category.vat_category.to_string(), None, &category.amounts);
+        }
+        for invoice_type in &self.by_invoice_type {
+            amounts("invoice_type", &invoice_type.invoice_type.to_string(), Some(invoice_type.invoices), &invoice_type.amounts);
+        }
+
+        csv
+    }
+}
+
+/// Builds a summary file by file, e.g. while results are streamed
+#[derive(Debug, Default)]
+pub struct BatchTally {
+    files: usize,
+    failed_files: usize,
+    invoices: usize,
+    green: usize,
+    yellow: usize,
+    red: usize,
+    error_codes: HashMap<String, usize>,
+    totals: Amounts,
+    by_vat_category: BTreeMap<VatCategory, CategoryAmounts>,
+    by_invoice_type: BTreeMap<InvoiceType, TypeAmounts>,
+}
+
+impl BatchTally {
+    pub fn record(&mut self, result: &BatchFileResult) {
+        self.files += 1;
+        if result.status == FileStatus::Error {
+            self.failed_files += 1;
+        }
+
+        let findings = result.reports.iter().chain(result.book.as_ref()).flat_map(|r| &r.errors);
+        for finding in findings.filter(|e| e.severity == Severity::Error) {
+            *self.error_codes.entry(finding.code.clone()).or_default() += 1;
+        }
+
+        for (i, report) in result.reports.iter().enumerate() {
+            self.invoices += 1;
+            match report.status {
+                ValidationStatus::Green => self.green += 1,
+                ValidationStatus::Yellow => self.yellow += 1,
+                ValidationStatus::Red => {
+                    self.red += 1;
+                    continue;
+                }
+            }
+
+            // Results stored before amounts were recorded have none
+            let Some(amounts) = result.amounts.get(i) else { continue };
+            let invoice_type = amounts.invoice_type;
+            self.totals.record(invoice_type, amounts.net, amounts.vat, amounts.gross);
+            for category in &amounts.by_vat_category {
+                let a = category.amounts;
+                self.by_vat_category.entry(category.vat_category)
+                    .or_insert_with(|| CategoryAmounts { vat_category: category.vat_category, amounts: Amounts::default() })
+                    .amounts.record(invoice_type, a.net, a.vat, a.gross);
+            }
+            let by_type = self.by_invoice_type.entry(invoice_type)
+                .or_insert_with(|| TypeAmounts { invoice_type, invoices: 0, amounts: Amounts::default() });
+            by_type.invoices += 1;
+            by_type.amounts.record(invoice_type, amounts.net, amounts.vat, amounts.gross);
+        }
+    }
+
+    pub fn summary(&self) -> BatchSummary {
+        let mut codes: Vec<CodeCount> = self.error_codes.iter()
+            .map(|(code, &count)| CodeCount { code: code.clone(), count })
+            .collect();
+        // Ties by code, so the list does not change between runs
+        codes.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.code.cmp(&b.code)));
+        codes.truncate(TOP_ERROR_CODES);
+
+        BatchSummary {
+            files: self.files,
+            failed_files: self.failed_files,
+            invoices: self.invoices,
+            green: self.green,
+            yellow: self.yellow,
+            red: self.red,
+            top_error_codes: codes,
+            totals: self.totals,
+            by_vat_category: self.by_vat_category.values().cloned().collect(),
+            by_invoice_type: self.by_invoice_type.values().cloned().collect(),
+        }
+    }
+}
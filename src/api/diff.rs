@@ -0,0 +1,77 @@
+use axum::{
+    extract::{FromRequest, Multipart, Request},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use serde::Deserialize;
+use crate::validation::diff;
+use super::validate::parse_document;
+
+/// JSON form of the request; the multipart form uses the same field names
+#[derive(Deserialize)]
+pub struct DiffRequest {
+    original: String,  // XML as first sent
+    corrected: String, // XML about to be re-sent
+}
+
+fn bad_request(error: &str, details: impl ToString) -> Response {
+    (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+        "error": error,
+        "details": details.to_string()
+    }))).into_response()
+}
+
+/// Compares a corrected XML document with the original, invoice by invoice
+pub async fn diff_documents(request: Request) -> Response {
+    let is_multipart = request.headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("multipart/form-data"));
+
+    let body = if is_multipart {
+        match Multipart::from_request(request, &()).await {
+            Ok(multipart) => read_multipart(multipart).await,
+            Err(e) => Err(e.body_text()),
+        }
+    } else {
+        match Json::<DiffRequest>::from_request(request, &()).await {
+            Ok(Json(body)) => Ok(body),
+            Err(e) => Err(e.body_text()),
+        }
+    };
+    let body = match body {
+        Ok(body) => body,
+        Err(e) => return bad_request("Invalid Diff Request", e),
+    };
+
+    let original = match parse_document(&body.original) {
+        Ok(invoices) => invoices,
+        Err(e) => return bad_request("Original XML Parse Failed", e),
+    };
+    let corrected = match parse_document(&body.corrected) {
+        Ok(invoices) => invoices,
+        Err(e) => return bad_request("Corrected XML Parse Failed", e),
+    };
+
+    (StatusCode::OK, Json(diff::compare_documents(&original, &corrected))).into_response()
+}
+
+async fn read_multipart(mut multipart: Multipart) -> Result<DiffRequest, String> {
+    let mut original = None;
+    let mut corrected = None;
+
+    while let Some(field) = multipart.next_field().await.map_err(|e| e.to_string())? {
+        let name = field.name().unwrap_or_default().to_string();
+        let content = field.text().await.map_err(|e| format!("Failed to read {}: {}", name, e))?;
+        match name.as_str() {
+            "original" => original = Some(content),
+            "corrected" => corrected = Some(content),
+            _ => {}
+        }
+    }
+
+    match (original, corrected) {
+        (Some(original), Some(corrected)) => Ok(DiffRequest { original, corrected }),
+        _ => Err("Both the `original` and the `corrected` documents are required".to_string()),
+    }
+}
@@ -0,0 +1,84 @@
+use axum::{
+    extract::{Extension, Multipart, Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use sqlx::types::Uuid;
+use crate::jobs::{runner, JobFile, JobStatus};
+use crate::persistence::batch_jobs;
+use crate::state::AppState;
+use super::auth::TenantId;
+use super::{error_response, internal_error};
+use super::validate::{read_batch_upload, summary_csv, ExportFormat, ExportParams, ValidateParams};
+
+/// Stores the uploaded files (archives expanded) as a job and returns at once; the files are validated in the background
+pub async fn submit_job(
+    State(state): State<AppState>,
+    Extension(tenant): Extension<TenantId>,
+    Query(params): Query<ValidateParams>,
+    headers: HeaderMap,
+    mut multipart: Multipart
+) -> Response {
+    let options = params.options(&headers);
+    let mut files = Vec::new();
+
+    for upload in read_batch_upload(&mut multipart).await {
+        match upload.content {
+            Ok(content) => files.push(JobFile { filename: upload.filename, archive: upload.archive, content }),
+            Err(e) => return error_response(StatusCode::BAD_REQUEST, "Invalid Batch Upload", format!("{}: {}", upload.filename, e)),
+        }
+    }
+    if files.is_empty() {
+        return error_response(StatusCode::BAD_REQUEST, "Invalid Batch Upload", "No files uploaded");
+    }
+
+    let id = match batch_jobs::create_job(&state.db, tenant.0, &options, &files).await {
+        Ok(id) => id,
+        Err(e) => return internal_error("Job Not Created", e),
+    };
+    runner::spawn(state, id);
+
+    (StatusCode::ACCEPTED, Json(serde_json::json!({
+        "job_id": id.to_string(),
+        "status": JobStatus::Queued,
+        "total_files": files.len()
+    }))).into_response()
+}
+
+/// Progress of the job and the results of the files processed so far; `?format=csv` gives the summary only
+pub async fn get_job(State(state): State<AppState>, Extension(tenant): Extension<TenantId>, Path(id): Path<String>, Query(export): Query<ExportParams>) -> Response {
+    let id = match Uuid::parse_str(&id) {
+        Ok(id) => id,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, "Invalid Job Id", e),
+    };
+
+    match batch_jobs::load_job(&state.db, tenant.0, id).await {
+        Ok(Some(job)) => match export.format {
+            ExportFormat::Json => (StatusCode::OK, Json(job)).into_response(),
+            ExportFormat::Csv => summary_csv(&job.summary),
+        },
+        Ok(None) => error_response(StatusCode::NOT_FOUND, "Job Not Found", id),
+        Err(e) => internal_error("Job Not Loaded", e),
+    }
+}
+
+/// Stops the job after the files already being validated; their results are kept
+pub async fn cancel_job(State(state): State<AppState>, Extension(tenant): Extension<TenantId>, Path(id): Path<String>) -> Response {
+    let id = match Uuid::parse_str(&id) {
+        Ok(id) => id,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, "Invalid Job Id", e),
+    };
+
+    match batch_jobs::request_cancel(&state.db, tenant.0, id).await {
+        Ok(Some(status)) if status.is_finished() => {
+            error_response(StatusCode::CONFLICT, "Job Already Finished", status.as_str())
+        }
+        Ok(Some(status)) => (StatusCode::ACCEPTED, Json(serde_json::json!({
+            "job_id": id.to_string(),
+            "status": status,
+            "cancel_requested": true
+        }))).into_response(),
+        Ok(None) => error_response(StatusCode::NOT_FOUND, "Job Not Found", id),
+        Err(e) => internal_error("Job Not Cancelled", e),
+    }
+}
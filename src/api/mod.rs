@@ -1,2 +1,27 @@
+pub mod diff;
 pub mod health;
 pub mod validate;
+pub mod jobs;
+pub mod batch_stream;
+pub mod batch_summary;
+pub mod auth;
+pub mod admin;
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+
+/// The `{error, details}` body every endpoint answers failures with
+pub(crate) fn error_response(status: StatusCode, error: &str, details: impl ToString) -> Response {
+    (status, Json(serde_json::json!({
+        "error": error,
+        "details": details.to_string()
+    }))).into_response()
+}
+
+/// A failure of the server itself: the cause goes to the log, the client only learns what did not happen
+pub(crate) fn internal_error(error: &str, cause: impl std::fmt::Display) -> Response {
+    tracing::error!("{}: {}", error, cause);
+    error_response(StatusCode::INTERNAL_SERVER_ERROR, error, "Internal server error, see the server log")
+}
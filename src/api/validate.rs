@@ -1,32 +1,189 @@
 use axum::{
-    extract::{State, Multipart},
-    http::StatusCode,
-    response::{IntoResponse, Json},
+    extract::{Extension, State, Multipart, Query},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Response},
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use crate::xml::parser::AadeBook;
 use crate::xml::normalizer::Normalizer;
-use crate::validation::business_rules::BusinessRules;
-use crate::validation::result::ValidationReport;
-use crate::persistence::validation_log;
+use crate::domain::invoice::Invoice;
+use crate::validation::business_rules::{BusinessRules, ValidationContext};
+use crate::validation::correlation_rules::ReferencedInvoice;
+use crate::validation::book_rules::BookRules;
+use crate::validation::result::{DocumentReport, ValidationReport};
+use crate::validation::revision::RevisionComparison;
+use crate::validation::risk::IssuerHistory;
+use crate::persistence::{island_registry, validation_log};
 use crate::state::AppState;
+use super::auth::TenantId;
+use super::batch_stream::{stream_batch, StreamFormat};
+use super::batch_summary::{BatchSummary, InvoiceAmounts};
+use crate::utils::archive::{self, ArchiveError, ArchiveKind, ArchiveLimits};
 use crate::utils::hash;
+use crate::i18n::lang::Lang;
 use quick_xml::de::from_str;
+use std::collections::HashMap;
 
-#[derive(Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileStatus {
+    Success, // Parsed and validated, whatever the findings
+    Error,   // Unreadable or not parseable, see `error_message`
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BatchFileResult {
-    filename: String,
-    status: String, // "success" (parsed ok) or "error" (xml parse failed)
-    reports: Vec<ValidationReport>,
-    error_message: Option<String>,
+    pub filename: String,
+    pub status: FileStatus,
+    pub reports: Vec<ValidationReport>,
+    pub book: Option<ValidationReport>, // Cross-invoice findings of the file
+    pub error_message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive: Option<String>, // Uploaded archive the file came from, `filename` is then its path inside it
+    #[serde(default)]
+    pub amounts: Vec<InvoiceAmounts>, // One per report, for the batch summary
+}
+
+/// The whole batch at once: every file's result and their roll-up
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchResponse {
+    pub results: Vec<BatchFileResult>,
+    pub summary: BatchSummary,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    Csv, // The summary only, for spreadsheets
+}
+
+/// `?format=csv` on batch endpoints
+#[derive(Debug, Default, Deserialize)]
+pub struct ExportParams {
+    #[serde(default)]
+    pub format: ExportFormat,
+    #[serde(default)]
+    pub summary: bool, // `?summary=true` answers `/validate/batch` as {results, summary} instead of the bare results
+}
+
+/// The summary as a CSV download
+pub(crate) fn summary_csv(summary: &BatchSummary) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"batch-summary.csv\""),
+        ],
+        summary.to_csv(),
+    ).into_response()
+}
+
+impl BatchFileResult {
+    pub(crate) fn failed(filename: String, error_message: String) -> Self {
+        Self {
+            filename,
+            status: FileStatus::Error,
+            reports: vec![],
+            book: None,
+            error_message: Some(error_message),
+            archive: None,
+            amounts: vec![],
+        }
+    }
+
+    pub(crate) fn in_archive(mut self, archive: Option<String>) -> Self {
+        self.archive = archive;
+        self
+    }
+}
+
+/// A file of a batch upload, sent as is or found in an uploaded archive
+pub(crate) struct BatchUpload {
+    pub filename: String,
+    pub archive: Option<String>,
+    pub content: Result<String, String>,
+}
+
+/// Reads every file of the form; `.zip` and `.tar.gz` archives are replaced by their XML entries
+pub(crate) async fn read_batch_upload(multipart: &mut Multipart) -> Vec<BatchUpload> {
+    let mut uploads = Vec::new();
+
+    while let Ok(Some(field)) = multipart.next_field().await {
+        let filename = field.file_name().unwrap_or("unknown.xml").to_string();
+
+        let bytes = match field.bytes().await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                uploads.push(BatchUpload { filename, archive: None, content: Err(format!("Failed to read file content: {}", e)) });
+                continue;
+            }
+        };
+
+        let Some(kind) = ArchiveKind::detect(&bytes) else {
+            let content = String::from_utf8(bytes.to_vec()).map_err(|e| format!("Failed to read file content: {}", e));
+            uploads.push(BatchUpload { filename, archive: None, content });
+            continue;
+        };
+
+        // Inflating is CPU work, kept off the async workers
+        let extracted = tokio::task::spawn_blocking(move || archive::extract_xml(&bytes, kind, &ArchiveLimits::default()))
+            .await
+            .unwrap_or_else(|e| Err(ArchiveError::Corrupt(e.to_string())));
+        match extracted {
+            Ok(entries) => uploads.extend(entries.into_iter().map(|entry| BatchUpload {
+                filename: entry.path,
+                archive: Some(filename.clone()),
+                content: String::from_utf8(entry.content).map_err(|e| format!("Failed to read file content: {}", e)),
+            })),
+            Err(e) => uploads.push(BatchUpload { filename, archive: None, content: Err(e.to_string()) }),
+        }
+    }
+
+    uploads
+}
+
+#[derive(Deserialize)]
+pub struct ValidateParams {
+    lang: Option<String>,
+    #[serde(default)]
+    stateful: bool, // `?stateful=true` compares each invoice with the validation log (series history, referenced invoices)
+    #[serde(default)]
+    compare_previous: bool, // `?compare_previous=true` diffs each invoice against its last logged validation
+    #[serde(default)]
+    book: bool, // `?book=true` answers `/validate` as {book, invoices} instead of the bare invoice reports
+}
+
+/// The request parameters once resolved; batch jobs keep them for their workers
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ValidateOptions {
+    pub lang: Lang,
+    pub stateful: bool,
+    pub compare_previous: bool,
+}
+
+impl ValidateParams {
+    // `?lang=en` wins over the Accept-Language header; Greek is the default
+    pub(crate) fn options(&self, headers: &HeaderMap) -> ValidateOptions {
+        let accept_language = headers.get(header::ACCEPT_LANGUAGE).and_then(|v| v.to_str().ok());
+        ValidateOptions {
+            lang: Lang::negotiate(self.lang.as_deref(), accept_language),
+            stateful: self.stateful,
+            compare_previous: self.compare_previous,
+        }
+    }
 }
 
 pub async fn validate_invoice(
     State(state): State<AppState>,
+    Extension(tenant): Extension<TenantId>,
+    Query(params): Query<ValidateParams>,
+    headers: HeaderMap,
     body: String
 ) -> impl IntoResponse {
-    match process_xml_content(&state, &body).await {
-        Ok(reports) => (StatusCode::OK, Json(reports)).into_response(),
+    match process_xml_content(&state, tenant, &body, &params.options(&headers)).await {
+        Ok((_, document)) if params.book => (StatusCode::OK, Json(document)).into_response(),
+        Ok((_, document)) => (StatusCode::OK, Json(document.invoices)).into_response(),
         Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({
             "error": "XML Parse Failed",
             "details": e
@@ -34,79 +191,182 @@ pub async fn validate_invoice(
     }
 }
 
+/// Answers with all results at once, or streams them when the client accepts NDJSON / SSE
 pub async fn validate_batch(
     State(state): State<AppState>,
+    Extension(tenant): Extension<TenantId>,
+    Query(params): Query<ValidateParams>,
+    Query(export): Query<ExportParams>,
+    headers: HeaderMap,
     mut multipart: Multipart
-) -> Json<Vec<BatchFileResult>> {
-    let mut results = Vec::new();
+) -> Response {
+    let options = params.options(&headers);
+    let uploads = read_batch_upload(&mut multipart).await;
 
-    while let Ok(Some(field)) = multipart.next_field().await {
-        let filename = field.file_name().unwrap_or("unknown.xml").to_string();
-        
-        // Read the field data as text
-        match field.text().await {
-            Ok(content) => {
-                match process_xml_content(&state, &content).await {
-                    Ok(reports) => {
-                        results.push(BatchFileResult {
-                            filename,
-                            status: "success".to_string(),
-                            reports,
-                            error_message: None,
-                        });
-                    },
-                    Err(e) => {
-                        results.push(BatchFileResult {
-                            filename,
-                            status: "error".to_string(),
-                            reports: vec![],
-                            error_message: Some(e),
-                        });
-                    }
-                }
-            },
-            Err(e) => {
-                results.push(BatchFileResult {
-                    filename,
-                    status: "error".to_string(),
-                    reports: vec![],
-                    error_message: Some(format!("Failed to read file content: {}", e)),
-                });
-            }
+    if export.format == ExportFormat::Json {
+        if let Some(format) = StreamFormat::negotiate(&headers) {
+            return stream_batch(state, tenant, uploads, options, format);
         }
     }
 
-    Json(results)
+    let mut results = Vec::new();
+    for upload in uploads {
+        results.push(validate_upload(&state, tenant, upload, &options).await);
+    }
+    let summary = BatchSummary::from_results(&results);
+
+    match export.format {
+        ExportFormat::Json if export.summary => Json(BatchResponse { results, summary }).into_response(),
+        ExportFormat::Json => Json(results).into_response(),
+        ExportFormat::Csv => summary_csv(&summary),
+    }
+}
+
+pub(crate) async fn validate_upload(state: &AppState, tenant: TenantId, upload: BatchUpload, options: &ValidateOptions) -> BatchFileResult {
+    let result = match upload.content {
+        Ok(content) => validate_file(state, tenant, upload.filename, &content, options).await,
+        Err(e) => BatchFileResult::failed(upload.filename, e),
+    };
+    result.in_archive(upload.archive)
+}
+
+/// One file of a batch, validated and logged
+pub(crate) async fn validate_file(state: &AppState, tenant: TenantId, filename: String, content: &str, options: &ValidateOptions) -> BatchFileResult {
+    match process_xml_content(state, tenant, content, options).await {
+        Ok((invoices, document)) => BatchFileResult {
+            filename,
+            status: FileStatus::Success,
+            reports: document.invoices,
+            book: Some(document.book),
+            error_message: None,
+            archive: None,
+            amounts: invoices.iter().map(InvoiceAmounts::from_invoice).collect(),
+        },
+        Err(e) => BatchFileResult::failed(filename, e),
+    }
 }
 
 // Helper function to process a single XML string (used by both single and batch endpoints)
-async fn process_xml_content(state: &AppState, content: &str) -> Result<Vec<ValidationReport>, String> {
+async fn process_xml_content(state: &AppState, tenant: TenantId, content: &str, options: &ValidateOptions) -> Result<(Vec<Invoice>, DocumentReport), String> {
+    let stateful = options.stateful;
     let xml_hash = hash::calculate_hash(content);
-    let book: AadeBook = from_str(content).map_err(|e| e.to_string())?;
+    let invoices = parse_document(content)?;
+
+    // Invoices of the document that others may reference by MARK
+    let in_document: Vec<ReferencedInvoice> = invoices.iter().filter_map(ReferencedInvoice::from_invoice).collect();
+
+    // Loaded once per issuer and before anything is logged, so the document's own invoices are not its history
+    let mut issuer_histories: HashMap<String, Option<IssuerHistory>> = HashMap::new();
+    for invoice in &invoices {
+        let vat = &invoice.issuer.vat_number;
+        if issuer_histories.contains_key(vat) {
+            continue;
+        }
+        let history = match validation_log::issuer_history(&state.db, tenant.0, vat).await {
+            Ok(h) => Some(h),
+            Err(e) => {
+                tracing::warn!("Failed to load issuer history: {}", e);
+                None
+            }
+        };
+        issuer_histories.insert(vat.clone(), history);
+    }
 
     let mut all_results = Vec::new();
 
-    for xml_inv in book.invoices {
-        match Normalizer::normalize(xml_inv) {
-            Ok(invoice) => {
-                    let res = BusinessRules::validate(&invoice);
-                    
-                    // Log to Database
-                    if let Err(e) = validation_log::log_validation(&state.db, &invoice, &xml_hash, &res).await {
-                        tracing::error!("Failed to log validation: {}", e);
-                    }
-
-                    all_results.push(res);
+    for invoice in &invoices {
+        let history = issuer_histories.get(&invoice.issuer.vat_number).and_then(Option::as_ref);
+        let series_history = if stateful {
+            match validation_log::series_history(&state.db, tenant.0, invoice).await {
+                Ok(h) => Some(h),
+                Err(e) => {
+                    tracing::warn!("Failed to load series history: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        let referenced = referenced_invoices(state, tenant, invoice, &in_document, stateful).await;
+        // Island rates belong to the seller's branch, whoever issued the document
+        let island_branches = match invoice.seller() {
+            Some(seller) => match island_registry::island_branches(&state.db, &state.island_registry, seller.vat_number).await {
+                Ok(registry) => registry,
+                Err(e) => {
+                    tracing::warn!("Failed to load island registry: {}", e);
+                    None
+                }
             },
-            Err(e) => {
-                // If normalization fails for one invoice, we currently treat it as a hard error for that invoice context
-                // But since we return Vec<ValidationReport>, we might need a way to represent a "Failed Normalization" report.
-                // For now, we'll return an error string to the caller, which might fail the whole file.
-                // IMPROVEMENT: Create a ValidationReport with a System Error for this specific failure.
-                return Err(format!("Normalization Failed: {}", e));
+            None => None,
+        };
+        let context = ValidationContext {
+            issuer_history: history,
+            series_history: series_history.as_ref(),
+            referenced: &referenced,
+            island_branches: island_branches.as_deref(),
+        };
+        let mut res = BusinessRules::validate_in_context(invoice, &context);
+
+        // Read before logging, or the invoice would be compared with itself
+        let previous = if options.compare_previous {
+            match validation_log::previous_attempt(&state.db, tenant.0, invoice).await {
+                Ok(previous) => previous,
+                Err(e) => {
+                    tracing::warn!("Failed to load previous attempt: {}", e);
+                    None
+                }
             }
+        } else {
+            None
+        };
+
+        // Log to Database (always in the default language)
+        if let Err(e) = validation_log::log_validation(&state.db, tenant.0, invoice, &xml_hash, &res).await {
+            tracing::error!("Failed to log validation: {}", e);
+        }
+        res.previous_attempt = previous.map(|p| RevisionComparison::between(&p, invoice, &res));
+
+        all_results.push(res);
+    }
+
+    // Document-level phase, once every invoice has been checked on its own
+    let mut document = DocumentReport {
+        book: BookRules::validate(&invoices),
+        invoices: all_results,
+    };
+    document.localize(options.lang);
+
+    Ok((invoices, document))
+}
+
+// If normalization fails for one invoice we fail the whole file, since the book rules need every invoice
+// IMPROVEMENT: Create a ValidationReport with a System Error for this specific failure.
+pub(crate) fn parse_document(content: &str) -> Result<Vec<Invoice>, String> {
+    let book: AadeBook = from_str(content).map_err(|e| e.to_string())?;
+    book.invoices.into_iter()
+        .map(Normalizer::normalize)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Normalization Failed: {}", e))
+}
+
+// Referenced invoices from the same document first; the log is only consulted in stateful mode
+async fn referenced_invoices(state: &AppState, tenant: TenantId, invoice: &Invoice, in_document: &[ReferencedInvoice], stateful: bool) -> Vec<ReferencedInvoice> {
+    let marks = &invoice.header.correlated_invoices;
+    let mut referenced: Vec<ReferencedInvoice> = in_document.iter()
+        .filter(|r| marks.contains(&r.mark))
+        .cloned()
+        .collect();
+
+    let missing: Vec<String> = marks.iter()
+        .filter(|m| !referenced.iter().any(|r| &r.mark == *m))
+        .cloned()
+        .collect();
+    if stateful && !missing.is_empty() {
+        match validation_log::referenced_invoices(&state.db, tenant.0, &missing).await {
+            Ok(logged) => referenced.extend(logged),
+            Err(e) => tracing::warn!("Failed to load referenced invoices: {}", e),
         }
     }
 
-    Ok(all_results)
-}
\ No newline at end of file
+    referenced
+}
@@ -5,6 +5,9 @@ pub struct Config {
     pub server_addr: String,
     pub cors_allowed_origins: Vec<String>,
     pub environment: Environment,
+    pub batch_workers: usize,
+    pub batch_upload_limit: usize, // Bytes accepted by /validate/batch and /jobs, where archives are uploaded
+    pub admin_api_key: Option<String>, // Without one the /admin endpoints refuse every request
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -46,11 +49,32 @@ impl Config {
             .map(|s| s.trim().to_string())
             .unwrap_or_else(|_| format!("0.0.0.0:{}", port.trim()));
 
+        let batch_workers = env::var("BATCH_WORKERS")
+            .ok()
+            .and_then(|s| s.trim().parse().ok())
+            .filter(|&n: &usize| n > 0)
+            .unwrap_or(4);
+
+        // Archives expand to at most 200MB (ArchiveLimits), so the upload itself rarely needs more than 100MB
+        let batch_upload_mb = env::var("BATCH_UPLOAD_MB")
+            .ok()
+            .and_then(|s| s.trim().parse().ok())
+            .filter(|&n: &usize| n > 0)
+            .unwrap_or(100);
+
+        let admin_api_key = env::var("ADMIN_API_KEY")
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+
         Self {
             database_url: env::var("DATABASE_URL").expect("DATABASE_URL must be set"),
             server_addr,
             cors_allowed_origins,
             environment,
+            batch_workers,
+            batch_upload_limit: batch_upload_mb * 1024 * 1024,
+            admin_api_key,
         }
     }
 }
@@ -9,3 +9,13 @@ pub struct IncomeClassification {
     pub classification_category: Option<String>,
     pub amount: Decimal,
 }
+
+/// Expense side of a classification, booked by the buyer (13.x / 14.x expense records)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpenseClassification {
+    #[serde(rename = "classificationType")]
+    pub classification_type: Option<String>,
+    #[serde(rename = "classificationCategory")]
+    pub classification_category: Option<String>,
+    pub amount: Decimal,
+}
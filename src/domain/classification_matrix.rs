@@ -0,0 +1,63 @@
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::OnceLock;
+
+// Embed the classification matrix into the binary
+const MATRIX_YAML: &str = include_str!("../../data/income_classifications.yaml");
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MatrixEntry {
+    pub category: String,
+    #[serde(default)]
+    pub types: Vec<String>, // Empty = the category is used without a classificationType
+    #[serde(default)]
+    pub vat_categories: Vec<String>, // Empty = any VAT treatment
+}
+
+/// Allowed income classificationType / classificationCategory pairs per invoice type
+#[derive(Debug, Deserialize)]
+pub struct ClassificationMatrix {
+    invoice_types: HashMap<String, Vec<MatrixEntry>>,
+}
+
+impl ClassificationMatrix {
+    pub fn load_from_yaml(content: &str) -> Result<Self, serde_yaml::Error> {
+        serde_yaml::from_str(content)
+    }
+
+    /// The embedded matrix, parsed on first use
+    pub fn get() -> &'static ClassificationMatrix {
+        static MATRIX: OnceLock<ClassificationMatrix> = OnceLock::new();
+        MATRIX.get_or_init(|| Self::load_from_yaml(MATRIX_YAML).expect("Embedded classification matrix is invalid"))
+    }
+
+    /// Invoice types without an entry are not checked against the matrix
+    pub fn covers(&self, invoice_type: &str) -> bool {
+        self.invoice_types.contains_key(invoice_type)
+    }
+
+    // Entries of the invoice type that apply to the VAT categories used on the invoice
+    fn entries<'a>(&'a self, invoice_type: &str, vat_categories: &'a [String]) -> impl Iterator<Item = &'a MatrixEntry> {
+        self.invoice_types.get(invoice_type)
+            .into_iter()
+            .flatten()
+            .filter(move |e| e.vat_categories.is_empty() || e.vat_categories.iter().any(|c| vat_categories.contains(c)))
+    }
+
+    pub fn allowed_categories(&self, invoice_type: &str, vat_categories: &[String]) -> Vec<String> {
+        self.entries(invoice_type, vat_categories)
+            .map(|e| e.category.clone())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    pub fn allowed_types(&self, invoice_type: &str, vat_categories: &[String], category: &str) -> Vec<String> {
+        self.entries(invoice_type, vat_categories)
+            .filter(|e| e.category == category)
+            .flat_map(|e| e.types.iter().cloned())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+}
@@ -0,0 +1,127 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::OnceLock;
+
+// Embed the country catalogue into the binary
+const COUNTRIES_YAML: &str = include_str!("../../data/countries.yaml");
+
+/// Where a counterpart stands for VAT purposes, seen from a Greek issuer
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum VatRegion {
+    Domestic,     // Greece
+    EuMember,     // Another member state of the EU VAT area
+    ThirdCountry, // Everything else, including EU territories outside the VAT area
+}
+
+impl std::fmt::Display for VatRegion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VatRegion::Domestic => write!(f, "Domestic"),
+            VatRegion::EuMember => write!(f, "EuMember"),
+            VatRegion::ThirdCountry => write!(f, "ThirdCountry"),
+        }
+    }
+}
+
+/// What the invoice supplies; a member can belong to the EU VAT area for goods only
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Supply {
+    Goods,
+    Services,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Membership {
+    pub from: NaiveDate,
+    pub until: Option<NaiveDate>,
+    #[serde(default)]
+    pub goods_only: bool, // e.g. Northern Ireland (XI) after Brexit
+}
+
+impl Membership {
+    pub fn covers(&self, date: NaiveDate) -> bool {
+        self.from <= date && self.until.is_none_or(|until| date <= until)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpecialTerritory {
+    pub name: String,
+    pub country: String,
+    pub code: Option<String>, // Own country code, when the territory has one
+    pub in_eu_vat_area: bool,
+    pub vat_country: Option<String>, // Treated as this country for VAT
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CountryCatalog {
+    countries: BTreeMap<String, String>,
+    vat_codes: BTreeMap<String, String>,
+    eu_membership: HashMap<String, Membership>,
+    special_territories: Vec<SpecialTerritory>,
+}
+
+impl CountryCatalog {
+    pub fn load_from_yaml(content: &str) -> Result<Self, serde_yaml::Error> {
+        serde_yaml::from_str(content)
+    }
+
+    /// The embedded catalogue, parsed on first use
+    pub fn get() -> &'static CountryCatalog {
+        static CATALOG: OnceLock<CountryCatalog> = OnceLock::new();
+        CATALOG.get_or_init(|| Self::load_from_yaml(COUNTRIES_YAML).expect("Embedded country catalogue is invalid"))
+    }
+
+    /// ISO 3166-1 alpha-2 codes plus the VAT-specific ones (EL, XI, IC, EA)
+    pub fn is_valid(&self, code: &str) -> bool {
+        self.countries.contains_key(code) || self.vat_codes.contains_key(code)
+    }
+
+    pub fn name(&self, code: &str) -> Option<&str> {
+        self.countries.get(code).or_else(|| self.vat_codes.get(code)).map(|s| s.as_str())
+    }
+
+    pub fn is_eu_member(&self, code: &str, date: NaiveDate) -> bool {
+        self.is_eu_member_for(code, date, Supply::Goods)
+    }
+
+    pub fn is_eu_member_for(&self, code: &str, date: NaiveDate, supply: Supply) -> bool {
+        let code = if code == "EL" { "GR" } else { code };
+        self.eu_membership.get(code)
+            .is_some_and(|m| m.covers(date) && (supply == Supply::Goods || !m.goods_only))
+    }
+
+    /// The special territory a code stands for, if any
+    pub fn territory(&self, code: &str) -> Option<&SpecialTerritory> {
+        self.special_territories.iter().find(|t| t.code.as_deref() == Some(code))
+    }
+
+    pub fn territories_of<'a>(&'a self, country: &'a str) -> impl Iterator<Item = &'a SpecialTerritory> {
+        self.special_territories.iter().filter(move |t| t.country == country)
+    }
+
+    /// VAT region of a country code on the given date, for a supply of goods; None for unknown codes
+    pub fn vat_region(&self, code: &str, date: NaiveDate) -> Option<VatRegion> {
+        self.vat_region_for(code, date, Supply::Goods)
+    }
+
+    /// VAT region of a country code on the given date for the kind of supply; None for unknown codes
+    pub fn vat_region_for(&self, code: &str, date: NaiveDate, supply: Supply) -> Option<VatRegion> {
+        if !self.is_valid(code) {
+            return None;
+        }
+
+        let code = match self.territory(code) {
+            Some(t) if !t.in_eu_vat_area => return Some(VatRegion::ThirdCountry),
+            Some(t) => t.vat_country.as_deref().unwrap_or(code),
+            None => code,
+        };
+
+        Some(match code {
+            "GR" | "EL" => VatRegion::Domestic,
+            _ if self.is_eu_member_for(code, date, supply) => VatRegion::EuMember,
+            _ => VatRegion::ThirdCountry,
+        })
+    }
+}
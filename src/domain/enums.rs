@@ -2,8 +2,9 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
+use super::country::Supply;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Default)]
 pub enum InvoiceType {
     #[serde(rename = "1.1")]
     SalesInvoice, // Τιμολόγιο Πώλησης
@@ -39,10 +40,86 @@ pub enum InvoiceType {
     RetailCreditNote, // Πιστωτικό Στοιχείο Λιανικής
     #[serde(rename = "11.5")]
     ServiceCreditNote, // Πιστωτικό Στοιχείο Παροχής
+    #[serde(rename = "13.1")]
+    ExpenseRetailPurchase, // Έξοδα - Αγορές Λιανικών Συναλλαγών ημεδαπής / αλλοδαπής
+    #[serde(rename = "13.2")]
+    ExpenseRetailService, // Παροχή Λιανικών Συναλλαγών ημεδαπής / αλλοδαπής
+    #[serde(rename = "13.3")]
+    ExpenseSharedUtilities, // Κοινόχρηστα
+    #[serde(rename = "13.4")]
+    ExpenseSubscriptions, // Συνδρομές
+    #[serde(rename = "13.30")]
+    ExpenseRetailOwnRecord, // Παραστατικά Οντότητας ως Αναγράφονται από την ίδια (Δυναμικό)
+    #[serde(rename = "13.31")]
+    ExpenseRetailCredit, // Πιστωτικό Στοιχ. Λιανικής ημεδαπής / αλλοδαπής
+    #[serde(rename = "14.1")]
+    ExpenseIntraAcquisition, // Τιμολόγιο / Ενδοκοινοτικές Αποκτήσεις
+    #[serde(rename = "14.2")]
+    ExpenseThirdCountryAcquisition, // Τιμολόγιο / Αποκτήσεις Τρίτων Χωρών
+    #[serde(rename = "14.3")]
+    ExpenseIntraServices, // Τιμολόγιο / Ενδοκοινοτική Λήψη Υπηρεσιών
+    #[serde(rename = "14.4")]
+    ExpenseThirdCountryServices, // Τιμολόγιο / Λήψη Υπηρεσιών Τρίτων Χωρών
+    #[serde(rename = "14.5")]
+    ExpenseSocialSecurity, // ΕΦΚΑ και λοιποί Ασφαλιστικοί Οργανισμοί
+    #[serde(rename = "14.30")]
+    ExpenseOwnRecord, // Παραστατικά Οντότητας ως Αναγράφονται από την ίδια (Δυναμικό)
+    #[serde(rename = "14.31")]
+    ExpenseCredit, // Πιστωτικό ημεδαπής / αλλοδαπής
     #[default]
     Unknown,
 }
 
+impl InvoiceType {
+    /// Services (2.x, 11.2, 11.5, 13.2, 14.3, 14.4) are placed differently from goods for VAT, see `Supply`
+    pub fn supply(&self) -> Supply {
+        match self {
+            InvoiceType::ServiceInvoice
+            | InvoiceType::ServiceInvoiceIntra
+            | InvoiceType::ServiceInvoiceThirdCountry
+            | InvoiceType::ServiceInvoiceRelated
+            | InvoiceType::ServiceReceipt
+            | InvoiceType::ServiceCreditNote
+            | InvoiceType::ExpenseRetailService
+            | InvoiceType::ExpenseIntraServices
+            | InvoiceType::ExpenseThirdCountryServices => Supply::Services,
+            _ => Supply::Goods,
+        }
+    }
+
+    /// Documents that take back (part of) an earlier one: 5.1, 11.4, 11.5 and the expense credits 13.31, 14.31
+    pub fn is_credit_note(&self) -> bool {
+        matches!(
+            self,
+            InvoiceType::CreditNote
+                | InvoiceType::RetailCreditNote
+                | InvoiceType::ServiceCreditNote
+                | InvoiceType::ExpenseRetailCredit
+                | InvoiceType::ExpenseCredit
+        )
+    }
+
+    /// Expense records (13.x, 14.x) are issued by the buyer to book what it received
+    pub fn is_expense_record(&self) -> bool {
+        matches!(
+            self,
+            InvoiceType::ExpenseRetailPurchase
+                | InvoiceType::ExpenseRetailService
+                | InvoiceType::ExpenseSharedUtilities
+                | InvoiceType::ExpenseSubscriptions
+                | InvoiceType::ExpenseRetailOwnRecord
+                | InvoiceType::ExpenseRetailCredit
+                | InvoiceType::ExpenseIntraAcquisition
+                | InvoiceType::ExpenseThirdCountryAcquisition
+                | InvoiceType::ExpenseIntraServices
+                | InvoiceType::ExpenseThirdCountryServices
+                | InvoiceType::ExpenseSocialSecurity
+                | InvoiceType::ExpenseOwnRecord
+                | InvoiceType::ExpenseCredit
+        )
+    }
+}
+
 impl fmt::Display for InvoiceType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -63,12 +140,25 @@ impl fmt::Display for InvoiceType {
             InvoiceType::SimplifiedInvoice => write!(f, "11.3"),
             InvoiceType::RetailCreditNote => write!(f, "11.4"),
             InvoiceType::ServiceCreditNote => write!(f, "11.5"),
+            InvoiceType::ExpenseRetailPurchase => write!(f, "13.1"),
+            InvoiceType::ExpenseRetailService => write!(f, "13.2"),
+            InvoiceType::ExpenseSharedUtilities => write!(f, "13.3"),
+            InvoiceType::ExpenseSubscriptions => write!(f, "13.4"),
+            InvoiceType::ExpenseRetailOwnRecord => write!(f, "13.30"),
+            InvoiceType::ExpenseRetailCredit => write!(f, "13.31"),
+            InvoiceType::ExpenseIntraAcquisition => write!(f, "14.1"),
+            InvoiceType::ExpenseThirdCountryAcquisition => write!(f, "14.2"),
+            InvoiceType::ExpenseIntraServices => write!(f, "14.3"),
+            InvoiceType::ExpenseThirdCountryServices => write!(f, "14.4"),
+            InvoiceType::ExpenseSocialSecurity => write!(f, "14.5"),
+            InvoiceType::ExpenseOwnRecord => write!(f, "14.30"),
+            InvoiceType::ExpenseCredit => write!(f, "14.31"),
             InvoiceType::Unknown => write!(f, "Unknown"),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Default)]
 pub enum VatCategory {
     #[serde(rename = "1")]
     #[default]
@@ -90,6 +180,17 @@ pub enum VatCategory {
 }
 
 impl VatCategory {
+    pub const ALL: [VatCategory; 8] = [
+        VatCategory::Vat24,
+        VatCategory::Vat13,
+        VatCategory::Vat6,
+        VatCategory::Vat17,
+        VatCategory::Vat9,
+        VatCategory::Vat4,
+        VatCategory::Vat0,
+        VatCategory::Excluded,
+    ];
+
     pub fn rate(&self) -> Decimal {
         match self {
             VatCategory::Vat24 => dec!(0.24),
@@ -101,6 +202,16 @@ impl VatCategory {
             VatCategory::Vat0 | VatCategory::Excluded => dec!(0.00),
         }
     }
+
+    /// The reduced rate on a registered Aegean island branch becomes the mainland rate elsewhere
+    pub fn mainland_equivalent(&self) -> Option<VatCategory> {
+        match self {
+            VatCategory::Vat17 => Some(VatCategory::Vat24),
+            VatCategory::Vat9 => Some(VatCategory::Vat13),
+            VatCategory::Vat4 => Some(VatCategory::Vat6),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for VatCategory {
@@ -118,26 +229,64 @@ impl fmt::Display for VatCategory {
     }
 }
 
-#[allow(dead_code)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PaymentMethod {
     #[serde(rename = "1")]
-    Cash, // Επαγγ. Λογαριασμός Πληρωμών Ημεδαπής
+    DomesticAccount, // Επαγγ. Λογαριασμός Πληρωμών Ημεδαπής
+    #[serde(rename = "2")]
+    ForeignAccount, // Επαγγ. Λογαριασμός Πληρωμών Αλλοδαπής
     #[serde(rename = "3")]
+    Cash, // Μετρητά
+    #[serde(rename = "4")]
     BankCheck, // Επιταγή
     #[serde(rename = "5")]
     Credit, // Επί Πιστώσει
+    #[serde(rename = "6")]
+    WebBanking, // Web Banking
     #[serde(rename = "7")]
     Pos, // POS / e-POS
+    #[serde(rename = "8")]
+    Iris, // Άμεσες Πληρωμές IRIS
+}
+
+impl PaymentMethod {
+    pub const ALL: [PaymentMethod; 8] = [
+        PaymentMethod::DomesticAccount,
+        PaymentMethod::ForeignAccount,
+        PaymentMethod::Cash,
+        PaymentMethod::BankCheck,
+        PaymentMethod::Credit,
+        PaymentMethod::WebBanking,
+        PaymentMethod::Pos,
+        PaymentMethod::Iris,
+    ];
+
+    pub fn from_code(code: i32) -> Option<Self> {
+        match code {
+            1 => Some(PaymentMethod::DomesticAccount),
+            2 => Some(PaymentMethod::ForeignAccount),
+            3 => Some(PaymentMethod::Cash),
+            4 => Some(PaymentMethod::BankCheck),
+            5 => Some(PaymentMethod::Credit),
+            6 => Some(PaymentMethod::WebBanking),
+            7 => Some(PaymentMethod::Pos),
+            8 => Some(PaymentMethod::Iris),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for PaymentMethod {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            PaymentMethod::Cash => write!(f, "1"),
-            PaymentMethod::BankCheck => write!(f, "3"),
+            PaymentMethod::DomesticAccount => write!(f, "1"),
+            PaymentMethod::ForeignAccount => write!(f, "2"),
+            PaymentMethod::Cash => write!(f, "3"),
+            PaymentMethod::BankCheck => write!(f, "4"),
             PaymentMethod::Credit => write!(f, "5"),
+            PaymentMethod::WebBanking => write!(f, "6"),
             PaymentMethod::Pos => write!(f, "7"),
+            PaymentMethod::Iris => write!(f, "8"),
         }
     }
 }
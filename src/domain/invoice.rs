@@ -1,10 +1,10 @@
 use serde::{Deserialize, Serialize};
 use chrono::{NaiveDate, NaiveTime};
 use rust_decimal::Decimal;
-use super::enums::{InvoiceType, VatCategory};
+use super::enums::{InvoiceType, PaymentMethod, VatCategory};
 use super::totals::InvoiceTotals;
 use super::vat::VatBreakdown;
-use super::classification::IncomeClassification;
+use super::classification::{ExpenseClassification, IncomeClassification};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InvoiceHeader {
@@ -16,6 +16,10 @@ pub struct InvoiceHeader {
     pub currency: String,
     // Exchange rate might be needed for non-EUR
     pub exchange_rate: Option<Decimal>,
+    #[serde(default)]
+    pub correlated_invoices: Vec<String>, // MARKs of the referenced invoices (credit notes etc.)
+    #[serde(default)]
+    pub self_pricing: bool, // Self-billing: the buyer issues the document on the seller's behalf
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,13 +44,31 @@ pub struct InvoiceLine {
     pub net_value: Decimal,
     pub vat_category: VatCategory,
     pub vat_amount: Decimal,
+    #[serde(default)]
+    pub vat_exemption_category: Option<i32>, // myDATA exemption code, e.g. 14 = art. 28
     pub quantity: Option<Decimal>,
     pub measurement_unit: Option<i32>, // Enum eventually
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentDetail {
+    pub payment_type: i32, // Raw myDATA code, see PaymentMethod; unknown codes are reported, not rejected
+    pub amount: Decimal,
+    pub info: Option<String>,
+}
+
+impl PaymentDetail {
+    pub fn method(&self) -> Option<PaymentMethod> {
+        PaymentMethod::from_code(self.payment_type)
+    }
+}
+
+// Fields added after the first release default when absent, so snapshots logged before them still load
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Invoice {
-    pub uid: Option<String>, // Generated internally for tracking
+    pub uid: Option<String>, // myDATA uid, when the document carries one
+    #[serde(default)]
+    pub mark: Option<String>, // MARK assigned by myDATA, present on already transmitted invoices
     pub header: InvoiceHeader,
     pub issuer: Issuer,
     pub counterpart: Option<Counterpart>, // Optional for B2C retail sometimes
@@ -54,4 +76,74 @@ pub struct Invoice {
     pub totals: InvoiceTotals,
     pub vat_breakdown: Vec<VatBreakdown>,
     pub income_classifications: Vec<IncomeClassification>,
+    #[serde(default)]
+    pub expense_classifications: Vec<ExpenseClassification>,
+    #[serde(default)]
+    pub payment_methods: Vec<PaymentDetail>, // Empty when the document has no paymentMethods section
+}
+
+/// Side of the transaction a party is on, independent of who issued the document
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PartyRole {
+    Seller,
+    Buyer,
+}
+
+/// Borrowed view of the issuer or the counterpart in its transaction role
+#[derive(Debug, Clone, Copy)]
+pub struct Party<'a> {
+    pub role: PartyRole,
+    pub field: &'static str, // "issuer" or "counterpart", prefix for finding fields
+    pub vat_number: &'a str,
+    pub country: &'a str,
+    pub branch: i32,
+}
+
+impl Invoice {
+    /// The buyer issues self-billed invoices and expense records (13.x, 14.x)
+    pub fn issuer_role(&self) -> PartyRole {
+        if self.header.self_pricing || self.header.invoice_type.is_expense_record() {
+            PartyRole::Buyer
+        } else {
+            PartyRole::Seller
+        }
+    }
+
+    pub fn issuer_party(&self) -> Party<'_> {
+        Party {
+            role: self.issuer_role(),
+            field: "issuer",
+            vat_number: &self.issuer.vat_number,
+            country: &self.issuer.country,
+            branch: self.issuer.branch,
+        }
+    }
+
+    pub fn counterpart_party(&self) -> Option<Party<'_>> {
+        let role = match self.issuer_role() {
+            PartyRole::Seller => PartyRole::Buyer,
+            PartyRole::Buyer => PartyRole::Seller,
+        };
+        self.counterpart.as_ref().map(|cp| Party {
+            role,
+            field: "counterpart",
+            vat_number: &cp.vat_number,
+            country: &cp.country,
+            branch: cp.branch,
+        })
+    }
+
+    pub fn seller(&self) -> Option<Party<'_>> {
+        self.party(PartyRole::Seller)
+    }
+
+    pub fn buyer(&self) -> Option<Party<'_>> {
+        self.party(PartyRole::Buyer)
+    }
+
+    fn party(&self, role: PartyRole) -> Option<Party<'_>> {
+        std::iter::once(self.issuer_party())
+            .chain(self.counterpart_party())
+            .find(|p| p.role == role)
+    }
 }
@@ -2,4 +2,6 @@ pub mod invoice;
 pub mod vat;
 pub mod totals;
 pub mod enums;
-pub mod classification;
\ No newline at end of file
+pub mod classification;
+pub mod country;
+pub mod classification_matrix;
@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::OnceLock;
+use super::lang::Lang;
+
+// Embed the message catalogues into the binary
+const EL_YAML: &str = include_str!("../../messages/el.yaml");
+const EN_YAML: &str = include_str!("../../messages/en.yaml");
+
+pub struct Catalog {
+    templates: HashMap<String, String>,
+}
+
+impl Catalog {
+    pub fn load_from_yaml(content: &str) -> Result<Self, serde_yaml::Error> {
+        Ok(Self { templates: serde_yaml::from_str(content)? })
+    }
+
+    /// The embedded catalogue for a language, parsed on first use
+    pub fn for_lang(lang: Lang) -> &'static Catalog {
+        static EL: OnceLock<Catalog> = OnceLock::new();
+        static EN: OnceLock<Catalog> = OnceLock::new();
+
+        match lang {
+            Lang::El => EL.get_or_init(|| Self::load_from_yaml(EL_YAML).expect("Embedded Greek catalogue is invalid")),
+            Lang::En => EN.get_or_init(|| Self::load_from_yaml(EN_YAML).expect("Embedded English catalogue is invalid")),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.templates.get(key).map(|s| s.as_str())
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.templates.keys().map(|k| k.as_str())
+    }
+}
+
+/// A translatable message: catalogue key plus named parameters, rendered on demand
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Message {
+    pub key: String,
+    pub params: BTreeMap<String, String>,
+    /// Template used when no catalogue has the key (e.g. custom YAML rules)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<String>,
+}
+
+impl Message {
+    pub fn new(key: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn with(mut self, name: &str, value: impl ToString) -> Self {
+        self.params.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    pub fn with_fallback(mut self, template: Option<&str>) -> Self {
+        self.fallback = template.map(|s| s.to_string());
+        self
+    }
+
+    /// Lookup order: requested language, the message's own fallback, Greek, the bare key
+    pub fn render(&self, lang: Lang) -> String {
+        let template = Catalog::for_lang(lang).get(&self.key)
+            .or(self.fallback.as_deref())
+            .or_else(|| Catalog::for_lang(Lang::default()).get(&self.key))
+            .unwrap_or(&self.key);
+
+        interpolate(template, &self.params)
+    }
+}
+
+/// Replaces `{name}` placeholders; unknown placeholders are left as they are
+pub fn interpolate(template: &str, params: &BTreeMap<String, String>) -> String {
+    let mut out = template.to_string();
+    for (name, value) in params {
+        out = out.replace(&format!("{{{}}}", name), value);
+    }
+    out
+}
+
+/// Placeholder names used by a template, e.g. ["line"] for "Line {line} ..."
+pub fn placeholders(template: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start + 1..].find('}') else { break };
+        names.push(rest[start + 1..start + 1 + len].to_string());
+        rest = &rest[start + 1 + len..];
+    }
+    names.sort();
+    names.dedup();
+    names
+}
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+pub enum Lang {
+    #[serde(rename = "el")]
+    #[default]
+    El, // Ελληνικά
+    #[serde(rename = "en")]
+    En, // English
+}
+
+impl Lang {
+    /// Parses a language tag such as "en", "en-GB" or "el-GR"
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+        match primary.as_str() {
+            "el" | "gr" => Some(Lang::El),
+            "en" => Some(Lang::En),
+            _ => None,
+        }
+    }
+
+    /// Picks the language: an explicit query parameter wins, then the best
+    /// supported entry of the Accept-Language header, then Greek.
+    pub fn negotiate(query: Option<&str>, accept_language: Option<&str>) -> Self {
+        if let Some(lang) = query.and_then(Self::from_tag) {
+            return lang;
+        }
+
+        accept_language
+            .and_then(Self::from_accept_language)
+            .unwrap_or_default()
+    }
+
+    // e.g. "fr-FR, en;q=0.8, el;q=0.9" -> El
+    fn from_accept_language(header: &str) -> Option<Self> {
+        let mut best: Option<(Lang, f32)> = None;
+
+        for entry in header.split(',') {
+            let mut parts = entry.split(';');
+            let Some(lang) = parts.next().and_then(Self::from_tag) else { continue };
+
+            let quality = parts
+                .filter_map(|p| p.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+
+            if quality > 0.0 && best.is_none_or(|(_, q)| quality > q) {
+                best = Some((lang, quality));
+            }
+        }
+
+        best.map(|(lang, _)| lang)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_overrides_header() {
+        assert_eq!(Lang::negotiate(Some("en"), Some("el-GR")), Lang::En);
+    }
+
+    #[test]
+    fn test_accept_language_quality() {
+        assert_eq!(Lang::negotiate(None, Some("fr-FR, en;q=0.8, el;q=0.9")), Lang::El);
+        assert_eq!(Lang::negotiate(None, Some("en-GB,en;q=0.9")), Lang::En);
+    }
+
+    #[test]
+    fn test_defaults_to_greek() {
+        assert_eq!(Lang::negotiate(Some("de"), Some("fr, de;q=0.5")), Lang::El);
+        assert_eq!(Lang::negotiate(None, None), Lang::El);
+    }
+}
@@ -0,0 +1,67 @@
+pub mod runner;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::api::batch_summary::BatchSummary;
+use crate::api::validate::BatchFileResult;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Completed,
+    Cancelled,
+    Failed, // The job itself broke down, not its files
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Queued => "queued",
+            JobStatus::Running => "running",
+            JobStatus::Completed => "completed",
+            JobStatus::Cancelled => "cancelled",
+            JobStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "queued" => Some(JobStatus::Queued),
+            "running" => Some(JobStatus::Running),
+            "completed" => Some(JobStatus::Completed),
+            "cancelled" => Some(JobStatus::Cancelled),
+            "failed" => Some(JobStatus::Failed),
+            _ => None,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, JobStatus::Queued | JobStatus::Running)
+    }
+}
+
+/// A file of a job, stored until a worker has validated it
+#[derive(Debug, Clone)]
+pub struct JobFile {
+    pub filename: String,
+    pub archive: Option<String>,
+    pub content: String,
+}
+
+/// A batch validation as seen by a polling client
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchJob {
+    pub id: String,
+    pub status: JobStatus,
+    pub total_files: i32,
+    pub processed_files: i32,
+    pub cancel_requested: bool,
+    pub error: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub results: Vec<BatchFileResult>, // Processed files so far, in upload order
+    pub summary: BatchSummary,         // Of the processed files, final once the job is finished
+}
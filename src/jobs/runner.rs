@@ -0,0 +1,157 @@
+use std::sync::Arc;
+use std::time::Duration;
+use sqlx::types::Uuid;
+use tokio::sync::{watch, Semaphore};
+use tokio::task::JoinSet;
+use crate::api::auth::TenantId;
+use crate::api::validate::{validate_file, ValidateOptions};
+use crate::persistence::batch_jobs::{self, ClaimedJob};
+use crate::state::AppState;
+use super::JobStatus;
+
+// How often a running job tells the other instances it is still alive; well under batch_jobs' STALE_AFTER
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+// How often unclaimed jobs are looked for; under STALE_AFTER too, so a dead instance's job waits at most both
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Runs a newly submitted job in the background; a failure of the job itself is recorded on it
+pub fn spawn(state: AppState, id: Uuid) {
+    tokio::spawn(async move {
+        let outcome = match batch_jobs::start_job(&state.db, id, state.instance_id).await {
+            Ok(Some(job)) => run(&state, id, job).await,
+            Ok(None) => {
+                tracing::info!("Batch job {} is run by another instance", id);
+                Ok(())
+            }
+            Err(e) => Err(e),
+        };
+        record_failure(&state, id, outcome).await;
+    });
+}
+
+/// Keeps claiming the jobs no instance is running: left queued, interrupted by a shutdown or
+/// abandoned by an instance that died. Their processed files are kept
+pub fn spawn_sweeper(state: AppState) {
+    tokio::spawn(async move {
+        let mut ticks = tokio::time::interval(SWEEP_INTERVAL);
+        loop {
+            ticks.tick().await;
+            match resume_unfinished(&state).await {
+                Ok(0) => {}
+                Ok(n) => tracing::info!("Resumed {} unfinished batch jobs", n),
+                Err(e) => tracing::error!("Failed to resume batch jobs: {}", e),
+            }
+        }
+    });
+}
+
+async fn resume_unfinished(state: &AppState) -> anyhow::Result<usize> {
+    let claimed = batch_jobs::claim_unfinished(&state.db, state.instance_id).await?;
+    let count = claimed.len();
+    for (id, job) in claimed {
+        let state = state.clone();
+        tokio::spawn(async move {
+            let outcome = match job {
+                Ok(job) => run(&state, id, job).await,
+                Err(e) => Err(e),
+            };
+            record_failure(&state, id, outcome).await;
+        });
+    }
+    Ok(count)
+}
+
+async fn record_failure(state: &AppState, id: Uuid, outcome: anyhow::Result<()>) {
+    let Err(e) = outcome else { return };
+    tracing::error!("Batch job {} failed: {}", id, e);
+    if let Err(e) = batch_jobs::finish_job(&state.db, id, state.instance_id, JobStatus::Failed, Some(&e.to_string())).await {
+        tracing::error!("Failed to record the failure of batch job {}: {}", id, e);
+    }
+}
+
+async fn run(state: &AppState, id: Uuid, (tenant_id, options): ClaimedJob) -> anyhow::Result<()> {
+    let (lost_tx, lost) = watch::channel(false);
+    let heartbeat = tokio::spawn(heartbeat(state.clone(), id, lost_tx));
+    let outcome = process(state, id, TenantId(tenant_id), options, lost).await;
+    heartbeat.abort();
+    outcome
+}
+
+// Flags `lost` once another instance has taken the job over
+async fn heartbeat(state: AppState, id: Uuid, lost: watch::Sender<bool>) {
+    let mut ticks = tokio::time::interval(HEARTBEAT_INTERVAL);
+    ticks.tick().await; // The claim itself was the first beat
+    loop {
+        ticks.tick().await;
+        match batch_jobs::heartbeat(&state.db, id, state.instance_id).await {
+            Ok(true) => {}
+            Ok(false) => {
+                let _ = lost.send(true);
+                return;
+            }
+            Err(e) => tracing::error!("Failed to record the heartbeat of batch job {}: {}", id, e),
+        }
+    }
+}
+
+async fn process(state: &AppState, id: Uuid, tenant: TenantId, options: ValidateOptions, lost: watch::Receiver<bool>) -> anyhow::Result<()> {
+    let files = batch_jobs::pending_files(&state.db, id).await?;
+
+    let permits = Arc::new(Semaphore::new(state.batch_workers.max(1)));
+    let mut tasks = JoinSet::new();
+    let mut cancelled = false;
+
+    for (position, file) in files {
+        let permit = permits.clone().acquire_owned().await?;
+        if *lost.borrow() {
+            break;
+        }
+        // Checked before each file: the ones already running finish, no new one starts
+        if batch_jobs::is_cancel_requested(&state.db, id).await? {
+            cancelled = true;
+            break;
+        }
+
+        let state = state.clone();
+        tasks.spawn(async move {
+            let result = validate_file(&state, tenant, file.filename, &file.content, &options).await
+                .in_archive(file.archive);
+            let stored = batch_jobs::store_result(&state.db, id, state.instance_id, position, &result).await;
+            drop(permit);
+            match stored {
+                Ok(true) => Ok(()),
+                Ok(false) => {
+                    tracing::warn!("Batch job {} already had a result for file {}", id, position + 1);
+                    Ok(())
+                }
+                Err(e) => Err(format!("file {}: {}", position + 1, e)),
+            }
+        });
+    }
+
+    let mut failures = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => failures.push(e),
+            Err(e) => failures.push(e.to_string()),
+        }
+    }
+
+    // The new owner finishes the job, with the files left when it claimed it
+    if *lost.borrow() {
+        tracing::warn!("Batch job {} was taken over by another instance", id);
+        return Ok(());
+    }
+    if !failures.is_empty() {
+        let error = format!("Results not stored: {}", failures.join("; "));
+        return batch_jobs::finish_job(&state.db, id, state.instance_id, JobStatus::Failed, Some(&error)).await;
+    }
+    let status = if cancelled || batch_jobs::is_cancel_requested(&state.db, id).await? {
+        JobStatus::Cancelled
+    } else {
+        JobStatus::Completed
+    };
+    batch_jobs::finish_job(&state.db, id, state.instance_id, status, None).await
+}
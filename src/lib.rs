@@ -6,4 +6,6 @@ pub mod domain;
 pub mod validation;
 pub mod xml;
 pub mod persistence;
+pub mod jobs;
 pub mod utils;
+pub mod i18n;
@@ -1,7 +1,8 @@
 use axum::{
-    routing::{get, post},
+    routing::{delete, get, post},
     Router,
     extract::DefaultBodyLimit,
+    middleware,
 };
 use tower_http::trace::TraceLayer;
 use tower_http::cors::CorsLayer;
@@ -9,6 +10,7 @@ use sqlx::postgres::PgPoolOptions;
 
 use aade_validator::api;
 use aade_validator::config::Config;
+use aade_validator::jobs::runner;
 use aade_validator::state::AppState;
 
 #[tokio::main]
@@ -26,7 +28,19 @@ async fn main() -> anyhow::Result<()> {
     // Run migrations
     sqlx::migrate!("./migrations").run(&pool).await?;
 
-    let state = AppState { db: pool };
+    let state = AppState {
+        db: pool.clone(),
+        batch_workers: config.batch_workers,
+        admin_key_hash: config.admin_api_key.as_deref().map(|key| api::auth::hash_key(key).into()),
+        island_registry: Default::default(),
+        instance_id: sqlx::query_scalar("SELECT gen_random_uuid()").fetch_one(&pool).await?,
+    };
+    if state.admin_key_hash.is_none() {
+        tracing::warn!("No ADMIN_API_KEY set - tenants and API keys cannot be managed");
+    }
+
+    // Batch jobs interrupted by the last shutdown, or abandoned by another instance, continue where they stopped
+    runner::spawn_sweeper(state.clone());
 
     // CORS: Configure based on environment
     let cors = if config.environment == aade_validator::config::Environment::Production {
@@ -42,23 +56,45 @@ async fn main() -> anyhow::Result<()> {
 
         CorsLayer::new()
             .allow_origin(origins)
-            .allow_methods([axum::http::Method::GET, axum::http::Method::POST])
-            .allow_headers([axum::http::header::CONTENT_TYPE])
+            .allow_methods([axum::http::Method::GET, axum::http::Method::POST, axum::http::Method::DELETE])
+            .allow_headers([
+                axum::http::header::CONTENT_TYPE,
+                axum::http::header::AUTHORIZATION,
+                axum::http::HeaderName::from_static("x-api-key"),
+            ])
     } else {
         // Development: Permissive CORS for easier testing
         tracing::info!("Development mode - using permissive CORS");
         CorsLayer::permissive()
     };
 
+    // Everything but the health checks needs a tenant's API key
+    let tenant_routes = Router::new()
+        .route("/validate", post(api::validate::validate_invoice))
+        .route("/validate/batch", post(api::validate::validate_batch).layer(DefaultBodyLimit::max(config.batch_upload_limit)))
+        .route("/diff", post(api::diff::diff_documents))
+        .route("/jobs", post(api::jobs::submit_job).layer(DefaultBodyLimit::max(config.batch_upload_limit)))
+        .route("/jobs/:id", get(api::jobs::get_job))
+        .route("/jobs/:id/cancel", post(api::jobs::cancel_job))
+        .route_layer(middleware::from_fn_with_state(state.clone(), api::auth::require_api_key));
+
+    let admin_routes = Router::new()
+        .route("/admin/tenants", get(api::admin::list_tenants).post(api::admin::create_tenant))
+        .route("/admin/tenants/:id/keys", get(api::admin::list_keys).post(api::admin::issue_key))
+        .route("/admin/keys/:id/revoke", post(api::admin::revoke_key))
+        .route("/admin/island-branches", get(api::admin::list_island_branches).post(api::admin::add_island_branches))
+        .route("/admin/island-branches/:id", delete(api::admin::delete_island_branch))
+        .route_layer(middleware::from_fn_with_state(state.clone(), api::auth::require_admin_key));
+
     let app = Router::new()
         .route("/health/ready", get(api::health::readiness))
         .route("/health/live", get(api::health::liveness))
-        .route("/validate", post(api::validate::validate_invoice))
-        .route("/validate/batch", post(api::validate::validate_batch))
+        .merge(tenant_routes)
+        .merge(admin_routes)
         .with_state(state)
         .layer(TraceLayer::new_for_http())
         .layer(cors)
-        .layer(DefaultBodyLimit::max(10 * 1024 * 1024)); // 10MB limit
+        .layer(DefaultBodyLimit::max(10 * 1024 * 1024)); // 10MB limit, batch uploads have their own
 
     let listener = tokio::net::TcpListener::bind(&config.server_addr).await?;
     let addr = listener.local_addr()?;
@@ -0,0 +1,274 @@
+use chrono::{DateTime, Utc};
+use sqlx::types::Uuid;
+use sqlx::PgPool;
+use crate::api::batch_summary::BatchSummary;
+use crate::api::validate::{BatchFileResult, ValidateOptions};
+use crate::jobs::{BatchJob, JobFile, JobStatus};
+
+// status, total_files, processed_files, cancel_requested, error, created_at, started_at, finished_at
+type JobRow = (
+    String,
+    i32,
+    i32,
+    bool,
+    Option<String>,
+    Option<DateTime<Utc>>,
+    Option<DateTime<Utc>>,
+    Option<DateTime<Utc>>,
+);
+
+/// Stores the job with its files, so it can be resumed after a restart
+pub async fn create_job(pool: &PgPool, tenant_id: Uuid, options: &ValidateOptions, files: &[JobFile]) -> anyhow::Result<Uuid> {
+    let mut tx = pool.begin().await?;
+
+    let id: Uuid = sqlx::query_scalar(
+        "INSERT INTO batch_jobs (tenant_id, options, total_files) VALUES ($1, $2, $3) RETURNING id",
+    )
+    .bind(tenant_id)
+    .bind(serde_json::to_value(options)?)
+    .bind(files.len() as i32)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    for (position, file) in files.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO batch_job_files (job_id, position, filename, archive, content) VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(id)
+        .bind(position as i32)
+        .bind(&file.filename)
+        .bind(&file.archive)
+        .bind(&file.content)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(id)
+}
+
+/// The tenant's job with the results of the files processed so far, in upload order
+pub async fn load_job(pool: &PgPool, tenant_id: Uuid, id: Uuid) -> anyhow::Result<Option<BatchJob>> {
+    let row: Option<JobRow> = sqlx::query_as(
+        r#"
+        SELECT status, total_files, processed_files, cancel_requested, error, created_at, started_at, finished_at
+        FROM batch_jobs
+        WHERE id = $1 AND tenant_id = $2
+        "#,
+    )
+    .bind(id)
+    .bind(tenant_id)
+    .fetch_optional(pool)
+    .await?;
+
+    let Some((status, total_files, processed_files, cancel_requested, error, created_at, started_at, finished_at)) = row else {
+        return Ok(None);
+    };
+
+    let results: Vec<serde_json::Value> = sqlx::query_scalar(
+        "SELECT result_json FROM batch_job_files WHERE job_id = $1 AND result_json IS NOT NULL ORDER BY position",
+    )
+    .bind(id)
+    .fetch_all(pool)
+    .await?;
+
+    let results: Vec<BatchFileResult> = results.into_iter().map(serde_json::from_value).collect::<Result<_, _>>()?;
+    Ok(Some(BatchJob {
+        id: id.to_string(),
+        status: JobStatus::parse(&status).ok_or_else(|| anyhow::anyhow!("Unknown job status {}", status))?,
+        total_files,
+        processed_files,
+        cancel_requested,
+        error,
+        created_at,
+        started_at,
+        finished_at,
+        summary: BatchSummary::from_results(&results),
+        results,
+    }))
+}
+
+// A running job whose owner has not reported for this long is taken to be abandoned
+const STALE_AFTER: &str = "2 minutes";
+
+/// The tenant a claimed job runs for and the options its files are validated with
+pub type ClaimedJob = (Uuid, ValidateOptions);
+
+// id, tenant_id, options
+type ClaimedRow = (Uuid, Option<Uuid>, serde_json::Value);
+
+/// Claims the job for this instance and returns its tenant and the options its files are validated with;
+/// None if an instance (this one included, through `claim_unfinished`) is running it
+pub async fn start_job(pool: &PgPool, id: Uuid, owner: Uuid) -> anyhow::Result<Option<ClaimedJob>> {
+    let row: Option<ClaimedRow> = sqlx::query_as(
+        r#"
+        UPDATE batch_jobs
+        SET status = $3, owner = $2, heartbeat_at = CURRENT_TIMESTAMP, started_at = COALESCE(started_at, CURRENT_TIMESTAMP)
+        WHERE id = (
+            SELECT id FROM batch_jobs
+            WHERE id = $1
+              AND (status = $4
+                   OR (status = $3 AND (owner IS NULL OR heartbeat_at < CURRENT_TIMESTAMP - $5::INTERVAL)))
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING id, tenant_id, options
+        "#,
+    )
+    .bind(id)
+    .bind(owner)
+    .bind(JobStatus::Running.as_str())
+    .bind(JobStatus::Queued.as_str())
+    .bind(STALE_AFTER)
+    .fetch_optional(pool)
+    .await?;
+
+    row.map(|(_, tenant_id, options)| claimed_job(tenant_id, options)).transpose()
+}
+
+fn claimed_job(tenant_id: Option<Uuid>, options: serde_json::Value) -> anyhow::Result<ClaimedJob> {
+    // Submitted before tenants existed, its results would be logged for no one
+    let tenant_id = tenant_id.ok_or_else(|| anyhow::anyhow!("Job has no tenant"))?;
+    Ok((tenant_id, serde_json::from_value(options)?))
+}
+
+/// Tells the other instances the job is still being run; false once another instance has taken it over
+pub async fn heartbeat(pool: &PgPool, id: Uuid, owner: Uuid) -> anyhow::Result<bool> {
+    let updated = sqlx::query("UPDATE batch_jobs SET heartbeat_at = CURRENT_TIMESTAMP WHERE id = $1 AND owner = $2 AND status = $3")
+        .bind(id)
+        .bind(owner)
+        .bind(JobStatus::Running.as_str())
+        .execute(pool)
+        .await?;
+
+    Ok(updated.rows_affected() > 0)
+}
+
+/// Files without a result yet, by position
+pub async fn pending_files(pool: &PgPool, id: Uuid) -> anyhow::Result<Vec<(i32, JobFile)>> {
+    let rows: Vec<(i32, String, Option<String>, String)> = sqlx::query_as(
+        "SELECT position, filename, archive, content FROM batch_job_files WHERE job_id = $1 AND result_json IS NULL ORDER BY position",
+    )
+    .bind(id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter()
+        .map(|(position, filename, archive, content)| (position, JobFile { filename, archive, content }))
+        .collect())
+}
+
+/// Stores the file's result; false if it already had one (a resumed job). Fails once another instance
+/// has taken the job over, so two instances never both count a file
+pub async fn store_result(pool: &PgPool, id: Uuid, owner: Uuid, position: i32, result: &BatchFileResult) -> anyhow::Result<bool> {
+    let mut tx = pool.begin().await?;
+
+    // Locking the job row keeps a takeover from slipping in before the commit
+    let current: Option<Uuid> = sqlx::query_scalar("SELECT owner FROM batch_jobs WHERE id = $1 FOR UPDATE")
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+    if current != Some(owner) {
+        anyhow::bail!("Batch job {} was taken over by another instance", id);
+    }
+
+    let stored = sqlx::query(
+        r#"
+        UPDATE batch_job_files
+        SET result_json = $3, processed_at = CURRENT_TIMESTAMP
+        WHERE job_id = $1 AND position = $2 AND result_json IS NULL
+        "#,
+    )
+    .bind(id)
+    .bind(position)
+    .bind(serde_json::to_value(result)?)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected() > 0;
+
+    if stored {
+        sqlx::query("UPDATE batch_jobs SET processed_files = processed_files + 1 WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+    Ok(stored)
+}
+
+pub async fn is_cancel_requested(pool: &PgPool, id: Uuid) -> anyhow::Result<bool> {
+    Ok(sqlx::query_scalar("SELECT cancel_requested FROM batch_jobs WHERE id = $1")
+        .bind(id)
+        .fetch_one(pool)
+        .await?)
+}
+
+/// Flags the tenant's unfinished job for cancellation; returns the job's status, None if there is no such job
+pub async fn request_cancel(pool: &PgPool, tenant_id: Uuid, id: Uuid) -> anyhow::Result<Option<JobStatus>> {
+    let status: Option<String> = sqlx::query_scalar(
+        r#"
+        UPDATE batch_jobs
+        SET cancel_requested = (status IN ($3, $4)) OR cancel_requested
+        WHERE id = $1 AND tenant_id = $2
+        RETURNING status
+        "#,
+    )
+    .bind(id)
+    .bind(tenant_id)
+    .bind(JobStatus::Queued.as_str())
+    .bind(JobStatus::Running.as_str())
+    .fetch_optional(pool)
+    .await?;
+
+    status
+        .map(|s| JobStatus::parse(&s).ok_or_else(|| anyhow::anyhow!("Unknown job status {}", s)))
+        .transpose()
+}
+
+/// Records the outcome, unless another instance has taken the job over meanwhile
+pub async fn finish_job(pool: &PgPool, id: Uuid, owner: Uuid, status: JobStatus, error: Option<&str>) -> anyhow::Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE batch_jobs
+        SET status = $3, error = $4, finished_at = CURRENT_TIMESTAMP
+        WHERE id = $1 AND (owner = $2 OR owner IS NULL)
+        "#,
+    )
+    .bind(id)
+    .bind(owner)
+    .bind(status.as_str())
+    .bind(error)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Claims the queued jobs and those whose owner stopped reporting (a restart, a crashed instance), oldest first;
+/// jobs another instance is claiming at the same moment are skipped rather than waited for
+pub async fn claim_unfinished(pool: &PgPool, owner: Uuid) -> anyhow::Result<Vec<(Uuid, anyhow::Result<ClaimedJob>)>> {
+    let rows: Vec<ClaimedRow> = sqlx::query_as(
+        r#"
+        WITH claimed AS (
+            UPDATE batch_jobs
+            SET status = $2, owner = $1, heartbeat_at = CURRENT_TIMESTAMP, started_at = COALESCE(started_at, CURRENT_TIMESTAMP)
+            WHERE id IN (
+                SELECT id FROM batch_jobs
+                WHERE status = $3
+                   OR (status = $2 AND (owner IS NULL OR heartbeat_at < CURRENT_TIMESTAMP - $4::INTERVAL))
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, tenant_id, options, created_at
+        )
+        SELECT id, tenant_id, options FROM claimed ORDER BY created_at
+        "#,
+    )
+    .bind(owner)
+    .bind(JobStatus::Running.as_str())
+    .bind(JobStatus::Queued.as_str())
+    .bind(STALE_AFTER)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|(id, tenant_id, options)| (id, claimed_job(tenant_id, options))).collect())
+}
@@ -0,0 +1,154 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use sqlx::types::Uuid;
+use sqlx::PgPool;
+use crate::validation::island_rules::IslandBranch;
+use super::as_string;
+
+type IslandRow = (String, i32, String, NaiveDate, Option<NaiveDate>);
+
+// id, vat_number, branch, island, valid_from, valid_until, created_at
+type RegisteredRow = (Uuid, String, i32, String, NaiveDate, Option<NaiveDate>, Option<DateTime<Utc>>);
+
+// How long the answer to "is any branch registered?" is trusted before it is read again
+const STATUS_TTL: Duration = Duration::from_secs(60);
+
+/// Whether the registry has been set up, so validations do not ask the database for every invoice.
+/// Changes made through the admin routes apply at once, others (SQL, another instance) within STATUS_TTL.
+#[derive(Debug, Default)]
+pub struct RegistryStatus {
+    checked: Mutex<Option<(Instant, bool)>>,
+}
+
+impl RegistryStatus {
+    async fn is_configured(&self, pool: &PgPool) -> anyhow::Result<bool> {
+        if let Some((at, configured)) = *self.checked.lock().unwrap() {
+            if at.elapsed() < STATUS_TTL {
+                return Ok(configured);
+            }
+        }
+
+        let configured: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM island_branches)")
+            .fetch_one(pool)
+            .await?;
+        *self.checked.lock().unwrap() = Some((Instant::now(), configured));
+        Ok(configured)
+    }
+
+    fn set(&self, configured: bool) {
+        *self.checked.lock().unwrap() = Some((Instant::now(), configured));
+    }
+
+    fn forget(&self) {
+        *self.checked.lock().unwrap() = None;
+    }
+}
+
+/// A registration as listed to admins
+#[derive(Debug, Clone, Serialize)]
+pub struct RegisteredBranch {
+    #[serde(serialize_with = "as_string")]
+    pub id: Uuid,
+    #[serde(flatten)]
+    pub branch: IslandBranch,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Island registrations of the seller's branches; None while the registry is empty (not set up yet),
+/// so the reduced rates are not rejected for everyone before any branch has been registered
+pub async fn island_branches(pool: &PgPool, status: &RegistryStatus, vat_number: &str) -> anyhow::Result<Option<Vec<IslandBranch>>> {
+    if !status.is_configured(pool).await? {
+        return Ok(None);
+    }
+
+    let rows: Vec<IslandRow> = sqlx::query_as(
+        r#"
+        SELECT vat_number, branch, island, valid_from, valid_until
+        FROM island_branches
+        WHERE vat_number = $1
+        ORDER BY branch, valid_from
+        "#,
+    )
+    .bind(vat_number)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(Some(
+        rows.into_iter()
+            .map(|(vat_number, branch, island, valid_from, valid_until)| IslandBranch {
+                vat_number,
+                branch,
+                island,
+                valid_from,
+                valid_until,
+            })
+            .collect(),
+    ))
+}
+
+/// Every registration, or those of one VAT number
+pub async fn list_branches(pool: &PgPool, vat_number: Option<&str>) -> anyhow::Result<Vec<RegisteredBranch>> {
+    let rows: Vec<RegisteredRow> = sqlx::query_as(
+        r#"
+        SELECT id, vat_number, branch, island, valid_from, valid_until, created_at
+        FROM island_branches
+        WHERE $1::TEXT IS NULL OR vat_number = $1
+        ORDER BY vat_number, branch, valid_from
+        "#,
+    )
+    .bind(vat_number)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter()
+        .map(|(id, vat_number, branch, island, valid_from, valid_until, created_at)| RegisteredBranch {
+            id,
+            branch: IslandBranch { vat_number, branch, island, valid_from, valid_until },
+            created_at,
+        })
+        .collect())
+}
+
+/// Loads the registrations all together or not at all; returns their ids in the same order
+pub async fn add_branches(pool: &PgPool, status: &RegistryStatus, branches: &[IslandBranch]) -> anyhow::Result<Vec<Uuid>> {
+    let mut tx = pool.begin().await?;
+    let mut ids = Vec::with_capacity(branches.len());
+
+    for branch in branches {
+        let id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO island_branches (vat_number, branch, island, valid_from, valid_until)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id
+            "#,
+        )
+        .bind(&branch.vat_number)
+        .bind(branch.branch)
+        .bind(&branch.island)
+        .bind(branch.valid_from)
+        .bind(branch.valid_until)
+        .fetch_one(&mut *tx)
+        .await?;
+        ids.push(id);
+    }
+
+    tx.commit().await?;
+    if !ids.is_empty() {
+        status.set(true);
+    }
+    Ok(ids)
+}
+
+/// Removes a registration; false if there is no such one
+pub async fn delete_branch(pool: &PgPool, status: &RegistryStatus, id: Uuid) -> anyhow::Result<bool> {
+    let deleted = sqlx::query("DELETE FROM island_branches WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    // The registry may be empty now, the next validation reads it again
+    status.forget();
+    Ok(deleted.rows_affected() > 0)
+}
@@ -1,2 +1,12 @@
 pub mod db;
 pub mod validation_log;
+pub mod island_registry;
+pub mod batch_jobs;
+pub mod tenants;
+
+use sqlx::types::Uuid;
+
+// The uuid crate comes through sqlx without its serde support
+pub(crate) fn as_string<S: serde::Serializer>(id: &Uuid, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(id)
+}
@@ -0,0 +1,134 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::types::Uuid;
+use sqlx::PgPool;
+use super::as_string;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Tenant {
+    #[serde(serialize_with = "as_string")]
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// A key as listed to admins, without anything that would let it be used
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiKeyInfo {
+    #[serde(serialize_with = "as_string")]
+    pub id: Uuid,
+    pub key_prefix: String,
+    pub name: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+// id, name, created_at
+type TenantRow = (Uuid, String, Option<DateTime<Utc>>);
+
+// id, key_prefix, name, created_at, last_used_at, revoked_at
+type ApiKeyRow = (Uuid, String, Option<String>, Option<DateTime<Utc>>, Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+
+fn tenant((id, name, created_at): TenantRow) -> Tenant {
+    Tenant { id, name, created_at }
+}
+
+pub async fn create_tenant(pool: &PgPool, name: &str) -> anyhow::Result<Tenant> {
+    let row: TenantRow = sqlx::query_as("INSERT INTO tenants (name) VALUES ($1) RETURNING id, name, created_at")
+        .bind(name)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(tenant(row))
+}
+
+pub async fn list_tenants(pool: &PgPool) -> anyhow::Result<Vec<Tenant>> {
+    let rows: Vec<TenantRow> = sqlx::query_as("SELECT id, name, created_at FROM tenants ORDER BY created_at")
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.into_iter().map(tenant).collect())
+}
+
+pub async fn tenant_exists(pool: &PgPool, tenant_id: Uuid) -> anyhow::Result<bool> {
+    Ok(sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM tenants WHERE id = $1)")
+        .bind(tenant_id)
+        .fetch_one(pool)
+        .await?)
+}
+
+/// Stores a newly issued key by its hash; returns the key's id
+pub async fn store_key(pool: &PgPool, tenant_id: Uuid, name: Option<&str>, key_hash: &str, key_prefix: &str) -> anyhow::Result<Uuid> {
+    Ok(sqlx::query_scalar(
+        "INSERT INTO api_keys (tenant_id, name, key_hash, key_prefix) VALUES ($1, $2, $3, $4) RETURNING id",
+    )
+    .bind(tenant_id)
+    .bind(name)
+    .bind(key_hash)
+    .bind(key_prefix)
+    .fetch_one(pool)
+    .await?)
+}
+
+pub async fn list_keys(pool: &PgPool, tenant_id: Uuid) -> anyhow::Result<Vec<ApiKeyInfo>> {
+    let rows: Vec<ApiKeyRow> = sqlx::query_as(
+        r#"
+        SELECT id, key_prefix, name, created_at, last_used_at, revoked_at
+        FROM api_keys
+        WHERE tenant_id = $1
+        ORDER BY created_at
+        "#,
+    )
+    .bind(tenant_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter()
+        .map(|(id, key_prefix, name, created_at, last_used_at, revoked_at)| ApiKeyInfo {
+            id, key_prefix, name, created_at, last_used_at, revoked_at,
+        })
+        .collect())
+}
+
+/// Revokes the key, keeping the first revocation time; None if there is no such key
+pub async fn revoke_key(pool: &PgPool, key_id: Uuid) -> anyhow::Result<Option<DateTime<Utc>>> {
+    Ok(sqlx::query_scalar(
+        "UPDATE api_keys SET revoked_at = COALESCE(revoked_at, CURRENT_TIMESTAMP) WHERE id = $1 RETURNING revoked_at",
+    )
+    .bind(key_id)
+    .fetch_optional(pool)
+    .await?)
+}
+
+/// The tenant a live key belongs to. last_used_at is written at most once a minute per key,
+/// so a busy client does not turn every request into a write on the same row
+pub async fn authenticate(pool: &PgPool, key_hash: &str) -> anyhow::Result<Option<Uuid>> {
+    let key: Option<(Uuid, Uuid, bool)> = sqlx::query_as(
+        r#"
+        SELECT id, tenant_id, last_used_at IS NULL OR last_used_at < CURRENT_TIMESTAMP - INTERVAL '1 minute'
+        FROM api_keys
+        WHERE key_hash = $1 AND revoked_at IS NULL
+        "#,
+    )
+    .bind(key_hash)
+    .fetch_optional(pool)
+    .await?;
+
+    let Some((key_id, tenant_id, stale)) = key else {
+        return Ok(None);
+    };
+    if stale {
+        sqlx::query(
+            r#"
+            UPDATE api_keys
+            SET last_used_at = CURRENT_TIMESTAMP
+            WHERE id = $1 AND (last_used_at IS NULL OR last_used_at < CURRENT_TIMESTAMP - INTERVAL '1 minute')
+            "#,
+        )
+        .bind(key_id)
+        .execute(pool)
+        .await?;
+    }
+    Ok(Some(tenant_id))
+}
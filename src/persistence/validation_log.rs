@@ -1,23 +1,36 @@
+use sqlx::types::Uuid;
 use sqlx::PgPool;
 use crate::domain::invoice::Invoice;
 use crate::validation::result::{ValidationReport, ValidationStatus};
+use crate::validation::book_rules::BookRules;
+use crate::validation::correlation_rules::ReferencedInvoice;
+use crate::validation::history_rules::SeriesHistory;
+use crate::validation::revision::PreviousAttempt;
+use crate::validation::risk::IssuerHistory;
 
 pub async fn log_validation(
     pool: &PgPool,
+    tenant_id: Uuid,
     invoice: &Invoice,
     hash: &str,
     report: &ValidationReport,
 ) -> anyhow::Result<()> {
     // Serialize errors and suggestions for storage
     let report_json = serde_json::to_value(report)?;
+    let invoice_json = serde_json::to_value(invoice)?;
 
     // Determine validity based on status (Green/Yellow are considered valid for submission, Red is invalid)
     let is_valid = report.status != ValidationStatus::Red;
 
+    // Distinct VAT categories used, kept for the issuer's risk history
+    let mut vat_categories: Vec<String> = invoice.lines.iter().map(|l| l.vat_category.to_string()).collect();
+    vat_categories.sort();
+    vat_categories.dedup();
+
     sqlx::query(
         r#"
-        INSERT INTO validation_logs (invoice_hash, issuer_vat, invoice_series, invoice_aa, is_valid, errors_json)
-        VALUES ($1, $2, $3, $4, $5, $6)
+        INSERT INTO validation_logs (invoice_hash, issuer_vat, invoice_series, invoice_aa, is_valid, errors_json, counterpart_vat, vat_categories, content_hash, issue_date, mark, total_net_amount, total_vat_amount, invoice_json, tenant_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, CAST($12 AS NUMERIC), CAST($13 AS NUMERIC), $14, $15)
         "#,
     )
     .bind(hash)
@@ -26,8 +39,168 @@ pub async fn log_validation(
     .bind(&invoice.header.aa)
     .bind(is_valid)
     .bind(report_json)
+    .bind(invoice.counterpart.as_ref().map(|cp| &cp.vat_number))
+    .bind(&vat_categories)
+    .bind(BookRules::content_fingerprint(invoice))
+    .bind(invoice.header.issue_date)
+    .bind(&invoice.mark)
+    .bind(invoice.totals.total_net_amount.to_string())
+    .bind(invoice.totals.total_vat_amount.to_string())
+    .bind(invoice_json)
+    .bind(tenant_id)
     .execute(pool)
     .await?;
 
     Ok(())
 }
+
+/// Loads what the issuer has submitted before through the tenant, for the statistical risk signals
+pub async fn issuer_history(pool: &PgPool, tenant_id: Uuid, issuer_vat: &str) -> anyhow::Result<IssuerHistory> {
+    // One round trip: the count, the category counts as two parallel arrays and the counterparts
+    let (invoice_count, categories, category_counts, known_counterparts): (i64, Vec<String>, Vec<i64>, Vec<String>) = sqlx::query_as(
+        r#"
+        WITH logs AS (
+            SELECT counterpart_vat, vat_categories
+            FROM validation_logs
+            WHERE tenant_id = $1 AND issuer_vat = $2
+        ),
+        categories AS (
+            SELECT cat, COUNT(*) AS n
+            FROM logs, unnest(vat_categories) AS cat
+            GROUP BY cat
+        )
+        SELECT
+            (SELECT COUNT(*) FROM logs),
+            COALESCE((SELECT array_agg(cat ORDER BY cat) FROM categories), '{}'),
+            COALESCE((SELECT array_agg(n ORDER BY cat) FROM categories), '{}'),
+            COALESCE((SELECT array_agg(DISTINCT counterpart_vat) FROM logs WHERE counterpart_vat IS NOT NULL), '{}')
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(issuer_vat)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(IssuerHistory {
+        invoice_count,
+        vat_category_counts: categories.into_iter().zip(category_counts).collect(),
+        known_counterparts: known_counterparts.into_iter().collect(),
+    })
+}
+
+/// Loads earlier validations of the invoice's series, for the stateful checks
+pub async fn series_history(pool: &PgPool, tenant_id: Uuid, invoice: &Invoice) -> anyhow::Result<SeriesHistory> {
+    // Numbers are compared as the HIST checks read them, so "007" and "7" are the same invoice.
+    // The CASE keeps Postgres from casting text numbers, whatever order it evaluates the filters in.
+    let number: Option<i64> = invoice.header.aa.trim().parse().ok();
+
+    let same_number_hashes: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT DISTINCT content_hash
+        FROM validation_logs
+        WHERE tenant_id = $1 AND issuer_vat = $2 AND invoice_series = $3 AND content_hash IS NOT NULL
+          AND (invoice_aa = $4 OR CASE WHEN invoice_aa ~ '^[0-9]{1,18}$' THEN CAST(invoice_aa AS BIGINT) END = $5)
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(&invoice.issuer.vat_number)
+    .bind(&invoice.header.series)
+    .bind(&invoice.header.aa)
+    .bind(number)
+    .fetch_all(pool)
+    .await?;
+
+    // Only numeric aa values take part in the sequence; text numbering has no order to check.
+    // Earlier submissions of this same number are not its predecessor.
+    let last: Option<(String, Option<chrono::NaiveDate>)> = sqlx::query_as(
+        r#"
+        SELECT invoice_aa, issue_date
+        FROM validation_logs
+        WHERE tenant_id = $1 AND issuer_vat = $2 AND invoice_series = $3 AND invoice_aa ~ '^[0-9]{1,18}$'
+          AND CASE WHEN invoice_aa ~ '^[0-9]{1,18}$' THEN CAST(invoice_aa AS BIGINT) END IS DISTINCT FROM $4
+        ORDER BY CAST(invoice_aa AS BIGINT) DESC, issue_date DESC NULLS LAST
+        LIMIT 1
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(&invoice.issuer.vat_number)
+    .bind(&invoice.header.series)
+    .bind(number)
+    .fetch_optional(pool)
+    .await?;
+
+    let (last_aa, last_date) = match last {
+        Some((aa, date)) => (aa.parse().ok(), date),
+        None => (None, None),
+    };
+
+    Ok(SeriesHistory {
+        same_number_hashes,
+        last_aa,
+        last_date,
+    })
+}
+
+/// The latest validation of the same issuer / series / aa that kept the normalized invoice
+pub async fn previous_attempt(pool: &PgPool, tenant_id: Uuid, invoice: &Invoice) -> anyhow::Result<Option<PreviousAttempt>> {
+    let row: Option<(serde_json::Value, serde_json::Value, chrono::DateTime<chrono::Utc>)> = sqlx::query_as(
+        r#"
+        SELECT invoice_json, errors_json, created_at
+        FROM validation_logs
+        WHERE tenant_id = $1 AND issuer_vat = $2 AND invoice_series = $3 AND invoice_aa = $4
+          AND invoice_json IS NOT NULL AND errors_json IS NOT NULL
+        ORDER BY created_at DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(&invoice.issuer.vat_number)
+    .bind(&invoice.header.series)
+    .bind(&invoice.header.aa)
+    .fetch_optional(pool)
+    .await?;
+
+    row.map(|(invoice_json, report_json, validated_at)| {
+        Ok(PreviousAttempt {
+            invoice: serde_json::from_value(invoice_json)?,
+            report: serde_json::from_value(report_json)?,
+            validated_at,
+        })
+    })
+    .transpose()
+}
+
+// mark, counterpart_vat, total_net_amount, total_vat_amount, vat_categories
+type ReferencedRow = (String, Option<String>, Option<String>, Option<String>, Option<Vec<String>>);
+
+/// Looks up the tenant's logged invoices by MARK, latest validation first, for the correlation checks
+pub async fn referenced_invoices(pool: &PgPool, tenant_id: Uuid, marks: &[String]) -> anyhow::Result<Vec<ReferencedInvoice>> {
+    if marks.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let rows: Vec<ReferencedRow> = sqlx::query_as(
+        r#"
+        SELECT DISTINCT ON (mark) mark, counterpart_vat, CAST(total_net_amount AS TEXT), CAST(total_vat_amount AS TEXT), vat_categories
+        FROM validation_logs
+        WHERE tenant_id = $1 AND mark = ANY($2) AND total_net_amount IS NOT NULL
+        ORDER BY mark, created_at DESC
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(marks)
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter()
+        .map(|(mark, counterpart_vat, net, vat, vat_categories)| {
+            Ok(ReferencedInvoice {
+                mark,
+                counterpart_vat,
+                total_net_amount: net.unwrap_or_default().parse()?,
+                total_vat_amount: vat.unwrap_or_default().parse()?,
+                vat_categories: vat_categories.unwrap_or_default(),
+            })
+        })
+        .collect()
+}
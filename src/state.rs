@@ -1,6 +1,13 @@
+use std::sync::Arc;
+use sqlx::types::Uuid;
 use sqlx::PgPool;
+use crate::persistence::island_registry::RegistryStatus;
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: PgPool,
+    pub batch_workers: usize, // Files of one batch job validated in parallel
+    pub admin_key_hash: Option<Arc<str>>, // SHA-256 of ADMIN_API_KEY
+    pub island_registry: Arc<RegistryStatus>, // Whether any island branch is registered, cached
+    pub instance_id: Uuid, // Owner of the batch jobs this process runs, new on every start
 }
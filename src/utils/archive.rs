@@ -0,0 +1,260 @@
+use std::io::{Cursor, Read};
+use flate2::read::GzDecoder;
+use thiserror::Error;
+
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const GZIP_MAGIC: &[u8] = b"\x1f\x8b";
+
+// Below this much data the compression ratio says nothing, small XMLs compress well
+const RATIO_GRACE: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Zip,
+    TarGz,
+}
+
+impl ArchiveKind {
+    /// By content, the upload's file name may be missing or wrong
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(ZIP_MAGIC) {
+            Some(ArchiveKind::Zip)
+        } else if bytes.starts_with(GZIP_MAGIC) {
+            Some(ArchiveKind::TarGz)
+        } else {
+            None
+        }
+    }
+}
+
+/// Guards against zip bombs; the defaults fit an ERP export of a few thousand invoices
+#[derive(Debug, Clone, Copy)]
+pub struct ArchiveLimits {
+    pub max_entries: usize,
+    pub max_total_size: u64, // Uncompressed bytes of all XML entries
+    pub max_ratio: u64,      // Uncompressed / compressed
+}
+
+impl Default for ArchiveLimits {
+    fn default() -> Self {
+        Self {
+            max_entries: 10_000,
+            max_total_size: 200 * 1024 * 1024,
+            max_ratio: 100,
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ArchiveError {
+    #[error("Archive has more than {0} entries")]
+    TooManyEntries(usize),
+
+    #[error("Archive expands to more than {0} bytes")]
+    TooLarge(u64),
+
+    #[error("Archive entry {path} is compressed more than {max_ratio}:1")]
+    SuspiciousRatio { path: String, max_ratio: u64 },
+
+    #[error("Unreadable archive: {0}")]
+    Corrupt(String),
+}
+
+/// An XML file of the archive, by its path inside it
+#[derive(Debug, Clone)]
+pub struct ArchiveEntry {
+    pub path: String,
+    pub content: Vec<u8>,
+}
+
+/// The XML entries of the archive in archive order; other files and directories are skipped
+pub fn extract_xml(bytes: &[u8], kind: ArchiveKind, limits: &ArchiveLimits) -> Result<Vec<ArchiveEntry>, ArchiveError> {
+    let mut budget = Budget { limits, entries: 0, total: 0, compressed: 0 };
+    match kind {
+        ArchiveKind::Zip => extract_zip(bytes, &mut budget),
+        ArchiveKind::TarGz => extract_tar_gz(bytes, &mut budget),
+    }
+}
+
+fn extract_zip(bytes: &[u8], budget: &mut Budget) -> Result<Vec<ArchiveEntry>, ArchiveError> {
+    let corrupt = |e: zip::result::ZipError| ArchiveError::Corrupt(e.to_string());
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).map_err(corrupt)?;
+    // The central directory gives the count before anything is inflated
+    if archive.len() > budget.limits.max_entries {
+        return Err(ArchiveError::TooManyEntries(budget.limits.max_entries));
+    }
+
+    let mut entries = Vec::new();
+    for i in 0..archive.len() {
+        let file = archive.by_index(i).map_err(corrupt)?;
+        if file.is_dir() || !is_xml(file.name()) {
+            continue;
+        }
+        let path = file.name().to_string();
+        budget.compressed += file.compressed_size();
+        let content = budget.read(file, &path)?;
+        entries.push(ArchiveEntry { path, content });
+    }
+    Ok(entries)
+}
+
+fn extract_tar_gz(bytes: &[u8], budget: &mut Budget) -> Result<Vec<ArchiveEntry>, ArchiveError> {
+    let corrupt = |e: std::io::Error| ArchiveError::Corrupt(e.to_string());
+    let mut archive = tar::Archive::new(GzDecoder::new(bytes));
+    // Entries share one gzip stream, so their sizes are weighed against the whole archive
+    budget.compressed = bytes.len() as u64;
+
+    let mut entries = Vec::new();
+    for entry in archive.entries().map_err(corrupt)? {
+        let entry = entry.map_err(corrupt)?;
+        budget.count()?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry.path().map_err(corrupt)?.to_string_lossy().into_owned();
+        if !is_xml(&path) {
+            continue;
+        }
+        let content = budget.read(entry, &path)?;
+        entries.push(ArchiveEntry { path, content });
+    }
+    Ok(entries)
+}
+
+// macOS adds resource forks (`__MACOSX/._invoice.xml`) to the archives it creates
+fn is_xml(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    !path.starts_with("__MACOSX/") && !name.starts_with("._") && name.to_ascii_lowercase().ends_with(".xml")
+}
+
+struct Budget<'a> {
+    limits: &'a ArchiveLimits,
+    entries: usize,
+    total: u64,      // Inflated so far
+    compressed: u64, // What the inflated bytes came from
+}
+
+impl Budget<'_> {
+    fn count(&mut self) -> Result<(), ArchiveError> {
+        self.entries += 1;
+        if self.entries > self.limits.max_entries {
+            return Err(ArchiveError::TooManyEntries(self.limits.max_entries));
+        }
+        Ok(())
+    }
+
+    // Sizes in the archive headers can lie, so reading stops as soon as a limit is passed
+    fn read(&mut self, reader: impl Read, path: &str) -> Result<Vec<u8>, ArchiveError> {
+        let size_left = self.limits.max_total_size - self.total;
+        let ratio_left = self.compressed.saturating_mul(self.limits.max_ratio).max(RATIO_GRACE).saturating_sub(self.total);
+        let allowed = size_left.min(ratio_left);
+
+        let mut content = Vec::new();
+        reader.take(allowed + 1).read_to_end(&mut content)
+            .map_err(|e| ArchiveError::Corrupt(format!("{}: {}", path, e)))?;
+
+        if content.len() as u64 > allowed {
+            return Err(if allowed == size_left {
+                ArchiveError::TooLarge(self.limits.max_total_size)
+            } else {
+                ArchiveError::SuspiciousRatio { path: path.to_string(), max_ratio: self.limits.max_ratio }
+            });
+        }
+        self.total += content.len() as u64;
+        Ok(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use zip::write::SimpleFileOptions;
+
+    const XML: &[u8] = b"<InvoicesDoc></InvoicesDoc>";
+
+    fn zip_of(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (path, content) in files {
+            if path.ends_with('/') {
+                writer.add_directory(*path, SimpleFileOptions::default()).unwrap();
+            } else {
+                writer.start_file(*path, SimpleFileOptions::default()).unwrap();
+                writer.write_all(content).unwrap();
+            }
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn tar_gz_of(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        for (path, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, path, *content).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    fn paths(entries: &[ArchiveEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_only_xml_entries_are_extracted() {
+        let files: [(&str, &[u8]); 5] = [
+            ("2024/", b""),
+            ("2024/a.xml", XML),
+            ("2024/readme.txt", b"export"),
+            ("__MACOSX/2024/._a.xml", b"\0"),
+            ("2024/B.XML", XML),
+        ];
+        let zip = zip_of(&files);
+        assert_eq!(ArchiveKind::detect(&zip), Some(ArchiveKind::Zip));
+        let entries = extract_xml(&zip, ArchiveKind::Zip, &ArchiveLimits::default()).unwrap();
+        assert_eq!(paths(&entries), ["2024/a.xml", "2024/B.XML"]);
+        assert_eq!(entries[0].content, XML);
+
+        let tar_gz = tar_gz_of(&files[1..]);
+        assert_eq!(ArchiveKind::detect(&tar_gz), Some(ArchiveKind::TarGz));
+        let entries = extract_xml(&tar_gz, ArchiveKind::TarGz, &ArchiveLimits::default()).unwrap();
+        assert_eq!(paths(&entries), ["2024/a.xml", "2024/B.XML"]);
+    }
+
+    #[test]
+    fn test_plain_xml_is_not_an_archive() {
+        assert_eq!(ArchiveKind::detect(XML), None);
+    }
+
+    #[test]
+    fn test_entry_count_limit() {
+        let limits = ArchiveLimits { max_entries: 2, ..ArchiveLimits::default() };
+        let files: [(&str, &[u8]); 3] = [("a.xml", XML), ("b.xml", XML), ("c.xml", XML)];
+
+        assert_eq!(extract_xml(&zip_of(&files), ArchiveKind::Zip, &limits).unwrap_err(), ArchiveError::TooManyEntries(2));
+        assert_eq!(extract_xml(&tar_gz_of(&files), ArchiveKind::TarGz, &limits).unwrap_err(), ArchiveError::TooManyEntries(2));
+    }
+
+    #[test]
+    fn test_total_size_limit() {
+        let limits = ArchiveLimits { max_total_size: 40, ..ArchiveLimits::default() };
+        let files: [(&str, &[u8]); 2] = [("a.xml", XML), ("b.xml", XML)];
+
+        assert_eq!(extract_xml(&zip_of(&files), ArchiveKind::Zip, &limits).unwrap_err(), ArchiveError::TooLarge(40));
+        assert_eq!(extract_xml(&tar_gz_of(&files), ArchiveKind::TarGz, &limits).unwrap_err(), ArchiveError::TooLarge(40));
+    }
+
+    #[test]
+    fn test_compression_ratio_limit() {
+        let bomb = vec![b' '; 4 * 1024 * 1024];
+        let files: [(&str, &[u8]); 1] = [("bomb.xml", &bomb)];
+        let expected = ArchiveError::SuspiciousRatio { path: "bomb.xml".to_string(), max_ratio: 100 };
+
+        assert_eq!(extract_xml(&zip_of(&files), ArchiveKind::Zip, &ArchiveLimits::default()).unwrap_err(), expected);
+        assert_eq!(extract_xml(&tar_gz_of(&files), ArchiveKind::TarGz, &ArchiveLimits::default()).unwrap_err(), expected);
+    }
+}
@@ -0,0 +1,485 @@
+// Offline syntax and check-digit validation of EU VAT numbers (VIES formats).
+// Formats use 9 for a digit, A for a letter and X for a digit or letter.
+
+use super::afm;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VatNumberError {
+    UnsupportedCountry,
+    Format { format: &'static str },
+    CheckDigit { algorithm: &'static str },
+}
+
+/// EU member states by the code used on invoices (ISO 3166-1, GR for Greece) plus XI for Northern Ireland
+pub const EU_VAT_COUNTRIES: [&str; 28] = [
+    "AT", "BE", "BG", "CY", "CZ", "DE", "DK", "EE", "ES", "FI", "FR", "GR", "HR", "HU",
+    "IE", "IT", "LT", "LU", "LV", "MT", "NL", "PL", "PT", "RO", "SE", "SI", "SK", "XI",
+];
+
+pub fn is_eu_vat_country(country: &str) -> bool {
+    EU_VAT_COUNTRIES.contains(&vat_country(country))
+}
+
+// EL is the VAT prefix of Greece
+fn vat_country(country: &str) -> &str {
+    match country {
+        "EL" => "GR",
+        other => other,
+    }
+}
+
+/// Strips separators and a leading VAT prefix ("DE 136 695 976" -> "136695976")
+pub fn compact(country: &str, vat_number: &str) -> String {
+    let number: String = vat_number.chars()
+        .filter(|c| !matches!(c, ' ' | '.' | '-' | '/'))
+        .collect::<String>()
+        .to_uppercase();
+
+    let prefix = match vat_country(country) {
+        "GR" => "EL",
+        other => other,
+    };
+    number.strip_prefix(prefix)
+        .or_else(|| number.strip_prefix(vat_country(country)))
+        .map(str::to_string)
+        .unwrap_or(number)
+}
+
+/// Validates a VAT number against the national format and check-digit rule of `country`
+pub fn validate(country: &str, vat_number: &str) -> Result<(), VatNumberError> {
+    let country = vat_country(country);
+    let number = compact(country, vat_number);
+
+    match country {
+        "AT" => check_at(&number),
+        "BE" => check_be(&number),
+        "BG" => check_bg(&number),
+        "CY" => check_cy(&number),
+        "CZ" => check_cz(&number),
+        "DE" => check_de(&number),
+        "DK" => check_dk(&number),
+        "EE" => check_ee(&number),
+        "ES" => check_es(&number),
+        "FI" => check_fi(&number),
+        "FR" => check_fr(&number),
+        "GR" => check_gr(&number),
+        "HR" => check_hr(&number),
+        "HU" => check_hu(&number),
+        "IE" => check_ie(&number),
+        "IT" => check_it(&number),
+        "LT" => check_lt(&number),
+        "LU" => check_lu(&number),
+        "LV" => check_lv(&number),
+        "MT" => check_mt(&number),
+        "NL" => check_nl(&number),
+        "PL" => check_pl(&number),
+        "PT" => check_pt(&number),
+        "RO" => check_ro(&number),
+        "SE" => check_se(&number),
+        "SI" => check_si(&number),
+        "SK" => check_sk(&number),
+        "XI" => check_xi(&number),
+        _ => Err(VatNumberError::UnsupportedCountry),
+    }
+}
+
+// ---- helpers ----
+
+fn is_digits(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn digits(s: &str) -> Vec<u32> {
+    s.bytes().map(|b| (b - b'0') as u32).collect()
+}
+
+fn weighted_sum(s: &str, weights: &[u32]) -> u32 {
+    digits(s).iter().zip(weights).map(|(d, w)| d * w).sum()
+}
+
+fn format_ok(ok: bool, format: &'static str) -> Result<(), VatNumberError> {
+    if ok { Ok(()) } else { Err(VatNumberError::Format { format }) }
+}
+
+fn check_ok(ok: bool, algorithm: &'static str) -> Result<(), VatNumberError> {
+    if ok { Ok(()) } else { Err(VatNumberError::CheckDigit { algorithm }) }
+}
+
+// Luhn checksum of a digit string; 0 means valid
+fn luhn_checksum(s: &str) -> u32 {
+    digits(s).iter().rev().enumerate()
+        .map(|(i, &d)| if i % 2 == 1 { let x = d * 2; x / 10 + x % 10 } else { d })
+        .sum::<u32>() % 10
+}
+
+// ISO 7064 MOD 11,10 over the whole number, check digit included
+fn iso7064_mod11_10(s: &str) -> bool {
+    let check = digits(s).iter().fold(5, |check, &d| {
+        let product = (if check == 0 { 10 } else { check } * 2) % 11;
+        (product + d) % 10
+    });
+    check == 1
+}
+
+// ISO 7064 MOD 97-10 with letters as 10..35, computed piecewise to avoid big integers
+fn mod97(s: &str) -> u32 {
+    s.chars().fold(0, |rem, c| {
+        let value = c.to_digit(36).unwrap_or(0);
+        if value >= 10 { (rem * 100 + value) % 97 } else { (rem * 10 + value) % 97 }
+    })
+}
+
+// ---- national rules ----
+
+fn check_at(n: &str) -> Result<(), VatNumberError> {
+    format_ok(n.len() == 9 && n.starts_with('U') && is_digits(&n[1..]), "U99999999")?;
+    // Luhn-style sum over the 7 body digits (even positions doubled), shifted by 4
+    let sum: u32 = digits(&n[1..8]).iter().enumerate()
+        .map(|(i, &d)| if i % 2 == 1 { d * 2 / 10 + d * 2 % 10 } else { d })
+        .sum();
+    let check = (10 - (sum + 4) % 10) % 10;
+    check_ok(check == digits(&n[8..])[0], "Luhn + 4")
+}
+
+fn check_be(n: &str) -> Result<(), VatNumberError> {
+    let n = if n.len() == 9 { format!("0{}", n) } else { n.to_string() };
+    format_ok(n.len() == 10 && is_digits(&n) && (n.starts_with('0') || n.starts_with('1')), "0999999999")?;
+    let body: u64 = n[..8].parse().unwrap_or(0);
+    let check: u64 = n[8..].parse().unwrap_or(0);
+    check_ok(97 - body % 97 == check, "MOD 97")
+}
+
+fn check_bg(n: &str) -> Result<(), VatNumberError> {
+    format_ok((n.len() == 9 || n.len() == 10) && is_digits(n), "999999999 / 9999999999")?;
+    let d = digits(n);
+    if n.len() == 9 {
+        let mut check = weighted_sum(n, &[1, 2, 3, 4, 5, 6, 7, 8]) % 11;
+        if check == 10 {
+            check = weighted_sum(n, &[3, 4, 5, 6, 7, 8, 9, 10]) % 11;
+        }
+        return check_ok(check % 10 == d[8], "weighted MOD 11");
+    }
+    // Ten digits: personal number (EGN), foreigner number (PNF) or other legal subject
+    let egn = weighted_sum(n, &[2, 4, 8, 5, 10, 9, 7, 3, 6]) % 11 % 10 == d[9];
+    let pnf = weighted_sum(n, &[21, 19, 17, 13, 11, 9, 7, 3, 1]) % 10 == d[9];
+    let other = (11 - weighted_sum(n, &[4, 3, 2, 7, 6, 5, 4, 3, 2]) % 11) % 11 == d[9];
+    check_ok(egn || pnf || other, "EGN / PNF / weighted MOD 11")
+}
+
+fn check_cy(n: &str) -> Result<(), VatNumberError> {
+    format_ok(
+        n.len() == 9 && n.is_ascii() && is_digits(&n[..8]) && n.as_bytes()[8].is_ascii_uppercase()
+            && matches!(n.as_bytes()[0], b'0' | b'1' | b'3' | b'4' | b'5' | b'9') && !n.starts_with("12"),
+        "99999999A",
+    )?;
+    const EVEN: [u32; 10] = [1, 0, 5, 7, 9, 13, 15, 17, 19, 21];
+    let sum: u32 = digits(&n[..8]).iter().enumerate()
+        .map(|(i, &d)| if i % 2 == 0 { EVEN[d as usize] } else { d })
+        .sum();
+    let check = (b'A' + (sum % 26) as u8) as char;
+    check_ok(n.ends_with(check), "MOD 26 letter")
+}
+
+fn check_cz(n: &str) -> Result<(), VatNumberError> {
+    format_ok(matches!(n.len(), 8..=10) && is_digits(n), "99999999 / 999999999 / 9999999999")?;
+    let d = digits(n);
+    match n.len() {
+        // Legal entities
+        8 => {
+            format_ok(!n.starts_with('9'), "99999999")?;
+            let check = (11 - weighted_sum(n, &[8, 7, 6, 5, 4, 3, 2]) % 11) % 11;
+            let check = if check == 0 { 1 } else { check } % 10;
+            check_ok(check == d[7], "weighted MOD 11")
+        }
+        // Individuals without a birth number
+        9 if n.starts_with('6') => {
+            let sum = weighted_sum(&n[1..], &[8, 7, 6, 5, 4, 3, 2]) % 11;
+            let check = (18 - (10 + 11 - sum) % 11) % 10;
+            check_ok(check == d[8], "weighted MOD 11")
+        }
+        // Birth numbers: nine digits carry no check digit
+        9 => Ok(()),
+        _ => {
+            let body: u64 = n[..9].parse().unwrap_or(0);
+            check_ok((body % 11 % 10) as u32 == d[9], "MOD 11")
+        }
+    }
+}
+
+fn check_de(n: &str) -> Result<(), VatNumberError> {
+    format_ok(n.len() == 9 && is_digits(n) && !n.starts_with('0'), "999999999")?;
+    check_ok(iso7064_mod11_10(n), "ISO 7064 MOD 11,10")
+}
+
+fn check_dk(n: &str) -> Result<(), VatNumberError> {
+    format_ok(n.len() == 8 && is_digits(n) && !n.starts_with('0'), "99999999")?;
+    check_ok(weighted_sum(n, &[2, 7, 6, 5, 4, 3, 2, 1]).is_multiple_of(11), "weighted MOD 11")
+}
+
+fn check_ee(n: &str) -> Result<(), VatNumberError> {
+    format_ok(n.len() == 9 && is_digits(n) && n.starts_with("10"), "109999999")?;
+    check_ok(weighted_sum(n, &[3, 7, 1, 3, 7, 1, 3, 7, 1]).is_multiple_of(10), "weighted MOD 10")
+}
+
+fn check_es(n: &str) -> Result<(), VatNumberError> {
+    const FORMAT: &str = "X9999999X";
+    format_ok(n.len() == 9 && n.is_ascii() && is_digits(&n[1..8]), FORMAT)?;
+    let (first, last) = (n.as_bytes()[0], n.as_bytes()[8] as char);
+    const DNI_LETTERS: &[u8] = b"TRWAGMYFPDXBNJZSQVHLCKE";
+    let dni_letter = |body: &str| DNI_LETTERS[(body.parse::<u64>().unwrap_or(0) % 23) as usize] as char;
+
+    match first {
+        // Spanish nationals (DNI)
+        b'0'..=b'9' => check_ok(is_digits(&n[..8]) && dni_letter(&n[..8]) == last, "DNI MOD 23"),
+        // Foreigners (NIE): X/Y/Z stand for 0/1/2
+        b'X' | b'Y' | b'Z' => {
+            let body = format!("{}{}", first - b'X', &n[1..8]);
+            check_ok(dni_letter(&body) == last, "NIE MOD 23")
+        }
+        // Other natural persons
+        b'K' | b'L' | b'M' => check_ok(dni_letter(&n[1..8]) == last, "NIF MOD 23"),
+        // Legal entities (CIF): check digit or its letter
+        b'A'..=b'H' | b'J' | b'N' | b'P'..=b'S' | b'U' | b'V' | b'W' => {
+            let check = (10 - luhn_checksum(&format!("{}0", &n[1..8]))) % 10;
+            let letter = b"JABCDEFGHI"[check as usize] as char;
+            check_ok(last == char::from_digit(check, 10).unwrap_or('?') || last == letter, "CIF Luhn")
+        }
+        _ => Err(VatNumberError::Format { format: FORMAT }),
+    }
+}
+
+fn check_fi(n: &str) -> Result<(), VatNumberError> {
+    format_ok(n.len() == 8 && is_digits(n), "99999999")?;
+    check_ok(weighted_sum(n, &[7, 9, 10, 5, 8, 4, 2, 1]).is_multiple_of(11), "weighted MOD 11")
+}
+
+fn check_fr(n: &str) -> Result<(), VatNumberError> {
+    const ALPHABET: &str = "0123456789ABCDEFGHJKLMNPQRSTUVWXYZ";
+    format_ok(
+        n.len() == 11 && n.is_ascii() && is_digits(&n[2..]) && n[..2].chars().all(|c| ALPHABET.contains(c)),
+        "XX999999999",
+    )?;
+    let siren = &n[2..];
+    // SIREN numbers are Luhn-valid, except the 000 range reserved for special entities
+    if !siren.starts_with("000") && luhn_checksum(siren) != 0 {
+        return Err(VatNumberError::CheckDigit { algorithm: "SIREN Luhn" });
+    }
+
+    if is_digits(&n[..2]) {
+        let key: u64 = n[..2].parse().unwrap_or(0);
+        let siren: u64 = siren.parse().unwrap_or(0);
+        return check_ok(key == (siren * 100 + 12) % 97, "MOD 97 key");
+    }
+
+    // New-style alphanumeric key
+    let index = |c: char| ALPHABET.find(c).unwrap_or(0) as u64;
+    let (c0, c1) = (n.chars().next().unwrap_or('0'), n.chars().nth(1).unwrap_or('0'));
+    let check = if c0.is_ascii_digit() {
+        (index(c0) * 24 + index(c1)).saturating_sub(10)
+    } else {
+        (index(c0) * 34 + index(c1)).saturating_sub(100)
+    };
+    let siren: u64 = siren.parse().unwrap_or(0);
+    check_ok((siren + 1 + check / 11) % 11 == check % 11, "MOD 11 key")
+}
+
+fn check_gr(n: &str) -> Result<(), VatNumberError> {
+    format_ok(n.len() == 9 && is_digits(n), "999999999")?;
+    check_ok(afm::validate_afm(n), "AFM MOD 11")
+}
+
+fn check_hr(n: &str) -> Result<(), VatNumberError> {
+    format_ok(n.len() == 11 && is_digits(n), "99999999999")?;
+    check_ok(iso7064_mod11_10(n), "ISO 7064 MOD 11,10")
+}
+
+fn check_hu(n: &str) -> Result<(), VatNumberError> {
+    format_ok(n.len() == 8 && is_digits(n), "99999999")?;
+    check_ok(weighted_sum(n, &[9, 7, 3, 1, 9, 7, 3, 1]).is_multiple_of(10), "weighted MOD 10")
+}
+
+fn check_ie(n: &str) -> Result<(), VatNumberError> {
+    const FORMAT: &str = "9999999A / 9999999AA / 9A99999A";
+    format_ok(matches!(n.len(), 8 | 9) && n.is_ascii(), FORMAT)?;
+    // Old style 9A99999A (second character a letter, + or *) maps onto 0999999A
+    let b = n.as_bytes();
+    let n = if n.len() == 8 && (b[1].is_ascii_uppercase() || b[1] == b'+' || b[1] == b'*') {
+        format!("0{}{}{}", &n[2..7], &n[..1], &n[7..])
+    } else {
+        n.to_string()
+    };
+    let b = n.as_bytes();
+    format_ok(
+        is_digits(&n[..7]) && b[7].is_ascii_uppercase() && (n.len() == 8 || b"ABCDEFGHIW".contains(&b[8])),
+        FORMAT,
+    )?;
+
+    const ALPHABET: &[u8] = b"WABCDEFGHIJKLMNOPQRSTUV";
+    let extra = n.as_bytes().get(8)
+        .and_then(|c| ALPHABET.iter().position(|a| a == c))
+        .unwrap_or(0) as u32;
+    let sum = weighted_sum(&n[..7], &[8, 7, 6, 5, 4, 3, 2]) + 9 * extra;
+    check_ok(ALPHABET[(sum % 23) as usize] == b[7], "MOD 23 letter")
+}
+
+fn check_it(n: &str) -> Result<(), VatNumberError> {
+    format_ok(n.len() == 11 && is_digits(n), "99999999999")?;
+    // Digits 8-10 are the issuing tax office
+    let office: u32 = n[7..10].parse().unwrap_or(0);
+    format_ok((1..=100).contains(&office) || matches!(office, 120 | 121 | 888 | 999), "99999999999")?;
+    check_ok(luhn_checksum(n) == 0, "Luhn")
+}
+
+fn check_lt(n: &str) -> Result<(), VatNumberError> {
+    format_ok(
+        (n.len() == 9 && n.as_bytes()[7] == b'1') || (n.len() == 12 && n.as_bytes()[10] == b'1'),
+        "999999919 / 999999999919",
+    )?;
+    format_ok(is_digits(n), "999999919 / 999999999919")?;
+    let body = &n[..n.len() - 1];
+    let weights: Vec<u32> = (0..body.len() as u32).map(|i| 1 + i % 9).collect();
+    let mut check = weighted_sum(body, &weights) % 11;
+    if check == 10 {
+        let weights: Vec<u32> = (0..body.len() as u32).map(|i| 1 + (i + 2) % 9).collect();
+        check = weighted_sum(body, &weights) % 11;
+    }
+    check_ok(check % 10 == digits(&n[n.len() - 1..])[0], "weighted MOD 11")
+}
+
+fn check_lu(n: &str) -> Result<(), VatNumberError> {
+    format_ok(n.len() == 8 && is_digits(n), "99999999")?;
+    let body: u64 = n[..6].parse().unwrap_or(0);
+    let check: u64 = n[6..].parse().unwrap_or(0);
+    check_ok(body % 89 == check, "MOD 89")
+}
+
+fn check_lv(n: &str) -> Result<(), VatNumberError> {
+    format_ok(n.len() == 11 && is_digits(n), "99999999999")?;
+    if n.as_bytes()[0] > b'3' {
+        // Legal entities
+        return check_ok(weighted_sum(n, &[9, 1, 4, 8, 3, 10, 2, 5, 7, 6, 1]) % 11 == 3, "weighted MOD 11");
+    }
+    if n.starts_with("32") {
+        // Personal codes issued since 2017 carry no check digit
+        return Ok(());
+    }
+    let check = (1 + weighted_sum(n, &[10, 5, 8, 4, 2, 1, 6, 3, 7, 9])) % 11 % 10;
+    check_ok(check == digits(&n[10..])[0], "weighted MOD 11")
+}
+
+fn check_mt(n: &str) -> Result<(), VatNumberError> {
+    format_ok(n.len() == 8 && is_digits(n) && !n.starts_with('0'), "99999999")?;
+    check_ok(weighted_sum(n, &[3, 4, 6, 7, 8, 9, 10, 1]).is_multiple_of(37), "weighted MOD 37")
+}
+
+fn check_nl(n: &str) -> Result<(), VatNumberError> {
+    format_ok(n.len() == 12 && n.is_ascii() && is_digits(&n[..9]) && &n[9..10] == "B" && is_digits(&n[10..]), "999999999B99")?;
+    // Either the BSN eleven-test or, for newer numbers, MOD 97 over "NL" + number
+    let bsn = weighted_sum(&n[..8], &[9, 8, 7, 6, 5, 4, 3, 2]) as i64 - digits(&n[8..9])[0] as i64;
+    let mod97_ok = mod97(&format!("NL{}", n)) == 1;
+    check_ok(bsn % 11 == 0 || mod97_ok, "MOD 11 / MOD 97")
+}
+
+fn check_pl(n: &str) -> Result<(), VatNumberError> {
+    format_ok(n.len() == 10 && is_digits(n), "9999999999")?;
+    let check = weighted_sum(n, &[6, 5, 7, 2, 3, 4, 5, 6, 7]) % 11;
+    check_ok(check == digits(&n[9..])[0], "weighted MOD 11")
+}
+
+fn check_pt(n: &str) -> Result<(), VatNumberError> {
+    format_ok(n.len() == 9 && is_digits(n) && !n.starts_with('0'), "999999999")?;
+    let check = (11 - weighted_sum(n, &[9, 8, 7, 6, 5, 4, 3, 2]) % 11) % 11 % 10;
+    check_ok(check == digits(&n[8..])[0], "weighted MOD 11")
+}
+
+fn check_ro(n: &str) -> Result<(), VatNumberError> {
+    format_ok((2..=10).contains(&n.len()) && is_digits(n) && !n.starts_with('0'), "99 to 9999999999")?;
+    let body = format!("{:0>9}", &n[..n.len() - 1]);
+    let check = 10 * weighted_sum(&body, &[7, 5, 3, 2, 1, 7, 5, 3, 2]) % 11 % 10;
+    check_ok(check == digits(&n[n.len() - 1..])[0], "weighted MOD 11")
+}
+
+fn check_se(n: &str) -> Result<(), VatNumberError> {
+    format_ok(n.len() == 12 && is_digits(n) && n.ends_with("01"), "999999999901")?;
+    check_ok(luhn_checksum(&n[..10]) == 0, "Luhn")
+}
+
+fn check_si(n: &str) -> Result<(), VatNumberError> {
+    format_ok(n.len() == 8 && is_digits(n) && !n.starts_with('0'), "99999999")?;
+    let check = 11 - weighted_sum(n, &[8, 7, 6, 5, 4, 3, 2]) % 11;
+    // A remainder of 0 would need an 11 and is never issued
+    check_ok(check != 11 && check % 10 == digits(&n[7..])[0], "weighted MOD 11")
+}
+
+fn check_sk(n: &str) -> Result<(), VatNumberError> {
+    format_ok(
+        n.len() == 10 && is_digits(n) && !n.starts_with('0') && b"234789".contains(&n.as_bytes()[2]),
+        "9999999999",
+    )?;
+    let value: u64 = n.parse().unwrap_or(1);
+    check_ok(value.is_multiple_of(11), "MOD 11")
+}
+
+fn check_xi(n: &str) -> Result<(), VatNumberError> {
+    const FORMAT: &str = "999999999 / 999999999999 / GD999 / HA999";
+    // Government departments and health authorities
+    if n.len() == 5 && (n.starts_with("GD") || n.starts_with("HA")) {
+        let number: u32 = n[2..].parse().map_err(|_| VatNumberError::Format { format: FORMAT })?;
+        return format_ok(if n.starts_with("GD") { number < 500 } else { number >= 500 }, FORMAT);
+    }
+    format_ok((n.len() == 9 || n.len() == 12) && is_digits(n), FORMAT)?;
+    let sum = weighted_sum(&n[..9], &[8, 7, 6, 5, 4, 3, 2, 10, 1]);
+    check_ok(sum.is_multiple_of(97) || (sum + 55).is_multiple_of(97), "MOD 97 / MOD 97-55")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_numbers() {
+        let valid = [
+            ("AT", "U13585627"), ("BE", "0403019261"), ("BG", "175074752"), ("CY", "10259033P"),
+            ("CZ", "25123891"), ("DE", "136695976"), ("DK", "13585628"), ("EE", "100931558"),
+            ("ES", "A13585625"), ("ES", "54362315K"), ("FI", "20774740"), ("FR", "40303265045"),
+            ("FR", "K7399859412"), ("GR", "090000045"), ("HR", "33392005961"), ("HU", "12892312"),
+            ("IE", "6433435F"), ("IE", "8Z49289F"), ("IT", "00743110157"), ("LT", "119511515"),
+            ("LU", "15027442"), ("LV", "40003521600"), ("MT", "11679112"), ("NL", "004495445B01"),
+            ("PL", "8567346215"), ("PT", "501964843"), ("RO", "18547290"), ("SE", "123456789701"),
+            ("SI", "50223054"), ("SK", "2022749619"), ("XI", "980780684"),
+        ];
+        let failures: Vec<_> = valid.iter()
+            .filter_map(|(country, number)| validate(country, number).err().map(|e| (country, number, e)))
+            .collect();
+        assert!(failures.is_empty(), "{:?}", failures);
+    }
+
+    #[test]
+    fn test_wrong_check_digit() {
+        for (country, number) in [("DE", "136695977"), ("IT", "00743110158"), ("NL", "004495446B01"), ("CY", "10259033Q")] {
+            assert!(matches!(validate(country, number), Err(VatNumberError::CheckDigit { .. })), "{} {}", country, number);
+        }
+    }
+
+    #[test]
+    fn test_non_ascii_numbers_are_format_errors() {
+        // Right byte length, but slicing would split the multibyte character
+        for (country, number) in [("CY", "1025903é"), ("FR", "4é03265045"), ("NL", "004495445é1")] {
+            assert!(matches!(validate(country, number), Err(VatNumberError::Format { .. })), "{} {}", country, number);
+        }
+    }
+
+    #[test]
+    fn test_prefix_and_separators_are_ignored() {
+        assert_eq!(validate("DE", "DE 136 695 976"), Ok(()));
+        assert_eq!(validate("GR", "EL090000045"), Ok(()));
+        assert_eq!(validate("EL", "090000045"), Ok(()));
+    }
+
+    #[test]
+    fn test_format_errors() {
+        assert_eq!(validate("AT", "13585627"), Err(VatNumberError::Format { format: "U99999999" }));
+        assert!(matches!(validate("FR", "ABC"), Err(VatNumberError::Format { .. })));
+        assert_eq!(validate("US", "123"), Err(VatNumberError::UnsupportedCountry));
+    }
+}
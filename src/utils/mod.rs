@@ -1,2 +1,4 @@
 pub mod hash;
-pub mod afm;
\ No newline at end of file
+pub mod afm;
+pub mod eu_vat;
+pub mod archive;
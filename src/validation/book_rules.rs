@@ -0,0 +1,193 @@
+use std::collections::{BTreeMap, HashMap};
+use crate::domain::invoice::Invoice;
+use crate::i18n::catalog::Message;
+use crate::utils::hash;
+use super::result::{FixAction, FixHint, ValidationReport};
+
+// Document-level rules: run after the per-invoice checks, over every invoice of one book (XML document).
+// Findings point at invoices by their 1-based position in the document, e.g. "invoice[2].invoiceHeader.aa".
+
+pub struct BookRules;
+
+impl BookRules {
+    pub fn validate(invoices: &[Invoice]) -> ValidationReport {
+        let mut report = ValidationReport::new();
+
+        Self::check_duplicate_identities(invoices, &mut report);
+        Self::check_duplicate_uids(invoices, &mut report);
+        Self::check_series_sequences(invoices, &mut report);
+        Self::check_duplicate_content(invoices, &mut report);
+
+        report
+    }
+
+    fn field(idx: usize, path: &str) -> String {
+        format!("invoice[{}].{}", idx + 1, path)
+    }
+
+    fn identity(invoice: &Invoice) -> String {
+        format!("{}/{}/{}", invoice.issuer.vat_number, invoice.header.series, invoice.header.aa)
+    }
+
+    fn check_duplicate_identities(invoices: &[Invoice], report: &mut ValidationReport) {
+        let mut first_seen: HashMap<String, usize> = HashMap::new();
+
+        for (idx, invoice) in invoices.iter().enumerate() {
+            let key = Self::identity(invoice);
+            let Some(&first) = first_seen.get(&key) else {
+                first_seen.insert(key, idx);
+                continue;
+            };
+
+            let field = Self::field(idx, "invoiceHeader.aa");
+            report.add_error(
+                "BOOK-001",
+                Message::new("BOOK-001")
+                    .with("series", &invoice.header.series)
+                    .with("aa", &invoice.header.aa)
+                    .with("invoice", idx + 1)
+                    .with("first", first + 1),
+                Some(&field),
+                Some(&invoice.header.aa),
+            );
+
+            // Propose the next free number of the series when numbering is numeric
+            let hint = FixHint::new("BOOK-001", &field, FixAction::Replace, Message::new("hint.BOOK-001"));
+            let next_aa = Self::numbered_series(invoices)
+                .get(&(invoice.issuer.vat_number.as_str(), invoice.header.series.as_str()))
+                .and_then(|entries| entries.iter().map(|(aa, _)| *aa).max())
+                .map(|max| max + 1);
+            report.add_suggestion(match next_aa {
+                Some(aa) => hint.with_proposed(aa),
+                None => hint,
+            });
+        }
+    }
+
+    fn check_duplicate_uids(invoices: &[Invoice], report: &mut ValidationReport) {
+        let mut first_seen: HashMap<&str, usize> = HashMap::new();
+
+        for (idx, invoice) in invoices.iter().enumerate() {
+            let Some(uid) = invoice.uid.as_deref() else { continue };
+            match first_seen.get(uid) {
+                Some(&first) => report.add_error(
+                    "BOOK-002",
+                    Message::new("BOOK-002").with("uid", uid).with("invoice", idx + 1).with("first", first + 1),
+                    Some(&Self::field(idx, "uid")),
+                    Some(uid),
+                ),
+                None => {
+                    first_seen.insert(uid, idx);
+                }
+            }
+        }
+    }
+
+    // (issuer, series) -> [(numeric aa, invoice index)], sorted by aa; non-numeric aa values are skipped.
+    // A number used again keeps only its first invoice: the rest are BOOK-001, not gaps or misdated numbers.
+    fn numbered_series(invoices: &[Invoice]) -> BTreeMap<(&str, &str), Vec<(u64, usize)>> {
+        let mut series: BTreeMap<(&str, &str), Vec<(u64, usize)>> = BTreeMap::new();
+        for (idx, invoice) in invoices.iter().enumerate() {
+            if let Ok(aa) = invoice.header.aa.trim().parse::<u64>() {
+                series.entry((invoice.issuer.vat_number.as_str(), invoice.header.series.as_str()))
+                    .or_default()
+                    .push((aa, idx));
+            }
+        }
+        for entries in series.values_mut() {
+            entries.sort();
+            entries.dedup_by_key(|(aa, _)| *aa);
+        }
+        series
+    }
+
+    fn check_series_sequences(invoices: &[Invoice], report: &mut ValidationReport) {
+        for ((_, series), entries) in Self::numbered_series(invoices) {
+            for pair in entries.windows(2) {
+                let (prev_aa, prev_idx) = pair[0];
+                let (aa, idx) = pair[1];
+
+                if aa > prev_aa + 1 {
+                    report.add_warning(
+                        "BOOK-003",
+                        Message::new("BOOK-003")
+                            .with("series", series)
+                            .with("from", prev_aa + 1)
+                            .with("to", aa - 1),
+                        Some(&Self::field(idx, "invoiceHeader.aa")),
+                        Some(&aa.to_string()),
+                    );
+                }
+
+                let (prev_date, date) = (invoices[prev_idx].header.issue_date, invoices[idx].header.issue_date);
+                if date < prev_date {
+                    report.add_warning(
+                        "BOOK-004",
+                        Message::new("BOOK-004")
+                            .with("series", series)
+                            .with("aa", aa)
+                            .with("date", date)
+                            .with("prev_aa", prev_aa)
+                            .with("prev_date", prev_date),
+                        Some(&Self::field(idx, "invoiceHeader.issueDate")),
+                        Some(&date.to_string()),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Hash of the parties, date, type, lines and totals, leaving out the invoice identity (uid, MARK, series, aa).
+    /// Same content under another number is usually a re-submission.
+    /// The fields are listed one by one in a fixed order: the hash is stored in the validation log,
+    /// so a field added to `Invoice` must not change it. Amounts are normalized, 100.00 and 100 are equal.
+    pub fn content_fingerprint(invoice: &Invoice) -> String {
+        let mut parts = vec![
+            invoice.issuer.vat_number.clone(),
+            invoice.counterpart.as_ref().map(|cp| cp.vat_number.clone()).unwrap_or_default(),
+            invoice.header.issue_date.to_string(),
+            invoice.header.invoice_type.to_string(),
+        ];
+        parts.extend(invoice.lines.iter().map(|line| {
+            format!("{}:{}:{}", line.net_value.normalize(), line.vat_amount.normalize(), line.vat_category)
+        }));
+
+        let totals = &invoice.totals;
+        parts.extend([
+            totals.total_net_amount,
+            totals.total_vat_amount,
+            totals.total_withheld_amount,
+            totals.total_fees_amount,
+            totals.total_stamp_duty_amount,
+            totals.total_deductions_amount,
+            totals.total_gross_amount,
+        ].iter().map(|amount| amount.normalize().to_string()));
+
+        hash::calculate_hash(&parts.join("|"))
+    }
+
+    fn check_duplicate_content(invoices: &[Invoice], report: &mut ValidationReport) {
+        let mut first_seen: HashMap<String, usize> = HashMap::new();
+
+        for (idx, invoice) in invoices.iter().enumerate() {
+            let fingerprint = Self::content_fingerprint(invoice);
+            match first_seen.get(&fingerprint) {
+                // Identical identities are already reported as BOOK-001
+                Some(&first) if Self::identity(&invoices[first]) != Self::identity(invoice) => report.add_warning(
+                    "BOOK-005",
+                    Message::new("BOOK-005")
+                        .with("invoice", idx + 1)
+                        .with("first", first + 1)
+                        .with("aa", &invoice.header.aa)
+                        .with("first_aa", &invoices[first].header.aa),
+                    Some(&Self::field(idx, "invoiceHeader")),
+                    None,
+                ),
+                Some(_) => {}
+                None => {
+                    first_seen.insert(fingerprint, idx);
+                }
+            }
+        }
+    }
+}
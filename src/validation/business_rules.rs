@@ -1,42 +1,88 @@
 use crate::domain::invoice::Invoice;
-use super::result::ValidationReport;
-use super::rules_engine::RulesEngine;
+use crate::domain::enums::PaymentMethod;
+use crate::domain::country::CountryCatalog;
+use crate::domain::classification_matrix::ClassificationMatrix;
+use super::result::{ExplainableError, FixAction, FixHint, Severity, ValidationReport};
+use crate::i18n::catalog::Message;
+use super::rules_engine::{RuleContext, RulesEngine};
+use super::risk::{IssuerHistory, RiskModel};
+use super::history_rules::{HistoryRules, SeriesHistory};
+use super::correlation_rules::{CorrelationRules, ReferencedInvoice};
+use super::island_rules::{IslandBranch, IslandRules};
+use super::reverse_charge_rules::ReverseChargeRules;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use chrono::Utc;
 
 use crate::utils::afm; // Import the AFM utility
+use crate::utils::eu_vat::{self, VatNumberError};
 
 // Embed the rules file into the binary
 const RULES_YAML: &str = include_str!("../../rules/mydata_v1.yaml");
 
+/// Everything known about the invoice beyond its own content; empty in stateless mode
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValidationContext<'a> {
+    pub issuer_history: Option<&'a IssuerHistory>, // Feeds the statistical risk signals
+    pub series_history: Option<&'a SeriesHistory>, // Stateful mode only
+    pub referenced: &'a [ReferencedInvoice],       // Known invoices among the correlatedInvoices
+    pub island_branches: Option<&'a [IslandBranch]>, // Seller's island registrations; None = no registry configured
+}
+
 pub struct BusinessRules;
 
 impl BusinessRules {
     pub fn validate(invoice: &Invoice) -> ValidationReport {
+        Self::validate_with_history(invoice, None)
+    }
+
+    /// Validates the invoice; the issuer history (if known) feeds the statistical risk signals
+    pub fn validate_with_history(invoice: &Invoice, history: Option<&IssuerHistory>) -> ValidationReport {
+        Self::validate_in_context(invoice, &ValidationContext { issuer_history: history, ..Default::default() })
+    }
+
+    pub fn validate_in_context(invoice: &Invoice, context: &ValidationContext) -> ValidationReport {
         let mut report = ValidationReport::new();
 
         // 1. Static Hardcoded Rules (Legacy/Complex Logic)
         Self::check_line_vat_consistency(invoice, &mut report);
         Self::check_classifications(invoice, &mut report);
+        Self::check_classification_matrix(invoice, &mut report);
         Self::check_totals(invoice, &mut report);
+        Self::check_country_codes(invoice, &mut report);
         Self::check_vat_numbers(invoice, &mut report);
         Self::check_dates(invoice, &mut report);
+        Self::check_payment_methods(invoice, &mut report);
+        CorrelationRules::check(invoice, context.referenced, &mut report);
+        ReverseChargeRules::check(invoice, &mut report);
+        if let Some(registry) = context.island_branches {
+            IslandRules::check(invoice, registry, &mut report);
+        }
 
         // 2. Dynamic Rules Engine (YAML)
-        match RulesEngine::load_from_yaml(RULES_YAML) {
-            Ok(engine) => engine.apply(invoice, &mut report),
+        let engine = RulesEngine::load_from_yaml(RULES_YAML);
+        match &engine {
+            Ok(engine) => engine.apply_in_context(invoice, &RuleContext { island_registry: context.island_branches.is_some() }, &mut report),
             Err(e) => {
                 // If rules fail to load, this is a system error, but we log it as an error in report for now
                 report.add_error(
                     "SYS-001", 
-                    &format!("Failed to load validation rules: {}", e), 
+                    Message::new("SYS-001").with("error", e), 
                     None, 
                     None
                 );
             }
         }
 
+        // 3. Series history (stateful mode only)
+        if let Some(series_history) = context.series_history {
+            HistoryRules::check(invoice, series_history, &mut report);
+        }
+
+        // 4. Risk scoring (rule weights + statistical signals)
+        let rules = engine.as_ref().map(|e| e.rules()).unwrap_or(&[]);
+        RiskModel::assess(invoice, &mut report, rules, context.issuer_history);
+
         report
     }
 
@@ -47,55 +93,121 @@ impl BusinessRules {
         if calc_net != invoice.totals.total_net_amount {
             report.add_error(
                 "BR-001", 
-                &format!("Calculated Net Amount ({}) mismatch", calc_net),
+                Message::new("BR-001").with("calculated", calc_net).with("declared", invoice.totals.total_net_amount),
                 Some("totalNetValue"),
                 Some(&invoice.totals.total_net_amount.to_string())
             );
+            report.add_suggestion(
+                FixHint::new("BR-001", "totalNetValue", FixAction::Replace, Message::new("hint.BR-001"))
+                    .with_expected(calc_net)
+                    .with_proposed(calc_net)
+            );
         }
 
         if calc_vat != invoice.totals.total_vat_amount {
             report.add_error(
                 "BR-002", 
-                &format!("Calculated VAT Amount ({}) mismatch", calc_vat),
+                Message::new("BR-002").with("calculated", calc_vat).with("declared", invoice.totals.total_vat_amount),
                 Some("totalVatAmount"),
                 Some(&invoice.totals.total_vat_amount.to_string())
             );
+            report.add_suggestion(
+                FixHint::new("BR-002", "totalVatAmount", FixAction::Replace, Message::new("hint.BR-002"))
+                    .with_expected(calc_vat)
+                    .with_proposed(calc_vat)
+            );
         }
-    }
 
-    fn check_vat_numbers(invoice: &Invoice, report: &mut ValidationReport) {
-        // Validate Issuer VAT (Only for GR)
-        if invoice.issuer.country == "GR" && !afm::validate_afm(&invoice.issuer.vat_number) {
+        // Gross is checked against the declared totals, so a net/VAT mismatch is not reported twice
+        let totals = &invoice.totals;
+        let calc_gross = totals.total_net_amount + totals.total_vat_amount - totals.total_withheld_amount
+            + totals.total_fees_amount + totals.total_stamp_duty_amount - totals.total_deductions_amount;
+        if calc_gross != totals.total_gross_amount {
             report.add_error(
-                "BR-003",
-                "Invalid Issuer VAT Number (AFM)",
-                Some("issuer.vatNumber"),
-                Some(&invoice.issuer.vat_number)
+                "BR-007",
+                Message::new("BR-007").with("calculated", calc_gross).with("declared", totals.total_gross_amount),
+                Some("totalGrossValue"),
+                Some(&totals.total_gross_amount.to_string())
+            );
+            report.add_suggestion(
+                FixHint::new("BR-007", "totalGrossValue", FixAction::Replace, Message::new("hint.BR-007"))
+                    .with_expected(calc_gross)
+                    .with_proposed(calc_gross)
             );
         }
+    }
+
+    fn check_country_codes(invoice: &Invoice, report: &mut ValidationReport) {
+        let catalog = CountryCatalog::get();
+        let countries = std::iter::once(("issuer.country", &invoice.issuer.country))
+            .chain(invoice.counterpart.as_ref().map(|cp| ("counterpart.country", &cp.country)));
 
-        // Validate Counterpart VAT (Only for GR)
-        if let Some(cp) = &invoice.counterpart {
-            if cp.country == "GR" && !afm::validate_afm(&cp.vat_number) {
+        for (field, country) in countries {
+            if !catalog.is_valid(country) {
                 report.add_error(
-                    "BR-004",
-                    "Invalid Counterpart VAT Number (AFM)",
-                    Some("counterpart.vatNumber"),
-                    Some(&cp.vat_number)
+                    "BR-COUNTRY",
+                    Message::new("BR-COUNTRY").with("country", country),
+                    Some(field),
+                    Some(country)
                 );
+                report.add_suggestion(FixHint::new("BR-COUNTRY", field, FixAction::Review, Message::new("hint.BR-COUNTRY")));
+            }
+        }
+    }
+
+    fn check_vat_numbers(invoice: &Invoice, report: &mut ValidationReport) {
+        // Issuer and counterpart are checked by country, whichever of them is the seller:
+        // AFM for GR, national rules for the other EU countries
+        for party in std::iter::once(invoice.issuer_party()).chain(invoice.counterpart_party()) {
+            let field = format!("{}.vatNumber", party.field);
+
+            if party.country != "GR" && eu_vat::is_eu_vat_country(party.country) {
+                Self::check_eu_vat_number(party.country, party.vat_number, &field, report);
+            } else if party.country == "GR" && !afm::validate_afm(party.vat_number) {
+                let code = if party.field == "issuer" { "BR-003" } else { "BR-004" };
+                report.add_error(code, Message::new(code), Some(&field), Some(party.vat_number));
+                report.add_suggestion(FixHint::new(code, &field, FixAction::Review, Message::new(&format!("hint.{}", code))));
             }
         }
     }
 
+    fn check_eu_vat_number(country: &str, vat_number: &str, field: &str, report: &mut ValidationReport) {
+        let message = match eu_vat::validate(country, vat_number) {
+            Ok(()) | Err(VatNumberError::UnsupportedCountry) => return,
+            Err(VatNumberError::Format { format }) => Message::new("BR-VAT-EU.format").with("country", country).with("format", format),
+            Err(VatNumberError::CheckDigit { algorithm }) => Message::new("BR-VAT-EU.checksum").with("country", country).with("algorithm", algorithm),
+        };
+
+        report.add_error("BR-VAT-EU", message, Some(field), Some(vat_number));
+        report.add_suggestion(FixHint::new("BR-VAT-EU", field, FixAction::Review, Message::new("hint.BR-VAT-EU")));
+    }
+
     fn check_dates(invoice: &Invoice, report: &mut ValidationReport) {
         let now = Utc::now().date_naive();
         if invoice.header.issue_date > now {
             report.add_error(
                 "BR-005", 
-                "Issue date cannot be in the future",
+                Message::new("BR-005").with("date", invoice.header.issue_date),
                 Some("invoiceHeader.issueDate"),
                 Some(&invoice.header.issue_date.to_string())
             );
+            // Only the issuer knows the real date, so nothing is proposed
+            report.add_suggestion(FixHint::new("BR-005", "invoiceHeader.issueDate", FixAction::Review, Message::new("hint.BR-005")));
+        }
+    }
+
+    fn check_payment_methods(invoice: &Invoice, report: &mut ValidationReport) {
+        let allowed: Vec<String> = PaymentMethod::ALL.iter().map(|m| m.to_string()).collect();
+
+        for (idx, payment) in invoice.payment_methods.iter().enumerate() {
+            if payment.method().is_none() {
+                let field = format!("paymentMethods[{}].type", idx + 1);
+                report.add_finding(
+                    ExplainableError::new("BR-014", Severity::Error, Message::new("BR-014").with("type", payment.payment_type), Some(&field), Some(&payment.payment_type.to_string()))
+                        .with_allowed_values(&allowed)
+                );
+                report.add_suggestion(Self::allowed_values_hint("BR-014", &field, FixAction::Replace, &allowed));
+            }
         }
     }
 
@@ -108,16 +220,97 @@ impl BusinessRules {
             
             // Tolerance of 0.05 EUR for rounding differences
             if diff > dec!(0.05) {
-                 report.add_error(
+                let field = format!("line[{}].vatAmount", line.line_number);
+                report.add_error(
                     "BR-VAT-CALC", 
-                    &format!(
-                        "VAT Amount mismatch on Line {}. Net: {}, Rate: {}, Expected: {}, Found: {}", 
-                        line.line_number, line.net_value, rate, expected_vat, line.vat_amount
-                    ),
-                    Some("invoiceDetails.vatAmount"),
+                    Message::new("BR-VAT-CALC")
+                        .with("line", line.line_number)
+                        .with("net", line.net_value)
+                        .with("rate", rate)
+                        .with("expected", expected_vat)
+                        .with("found", line.vat_amount),
+                    Some(&field),
                     Some(&line.vat_amount.to_string())
                 );
+                report.add_suggestion(
+                    FixHint::new("BR-VAT-CALC", &field, FixAction::Replace, Message::new("hint.BR-VAT-CALC"))
+                        .with_expected(expected_vat)
+                        .with_proposed(expected_vat)
+                );
+            }
+        }
+    }
+
+    // Replace with the only allowed value, otherwise let a human pick from the list
+    fn allowed_values_hint(code: &str, field: &str, action: FixAction, allowed: &[String]) -> FixHint {
+        let hint = FixHint::new(code, field, action, Message::new("hint.allowed_values").with("allowed", allowed.join(", ")))
+            .with_allowed_values(allowed);
+        match allowed {
+            [only] => hint.with_proposed(only),
+            _ => hint,
+        }
+    }
+
+    fn check_classification_matrix(invoice: &Invoice, report: &mut ValidationReport) {
+        let matrix = ClassificationMatrix::get();
+        let invoice_type = invoice.header.invoice_type.to_string();
+        if !matrix.covers(&invoice_type) {
+            return;
+        }
+
+        let mut vat_categories: Vec<String> = invoice.lines.iter().map(|l| l.vat_category.to_string()).collect();
+        vat_categories.sort();
+        vat_categories.dedup();
+        let allowed_categories = matrix.allowed_categories(&invoice_type, &vat_categories);
+
+        for (idx, classification) in invoice.income_classifications.iter().enumerate() {
+            let category_field = format!("incomeClassification[{}].classificationCategory", idx + 1);
+            let type_field = format!("incomeClassification[{}].classificationType", idx + 1);
+
+            // BR-011: category missing, BR-010: category not allowed for the invoice type
+            let category = match classification.classification_category.as_deref().map(str::trim) {
+                None | Some("") => {
+                    report.add_finding(
+                        ExplainableError::new("BR-011", Severity::Error, Message::new("BR-011").with("invoice_type", &invoice_type), Some(&category_field), None)
+                            .with_allowed_values(&allowed_categories)
+                    );
+                    report.add_suggestion(Self::allowed_values_hint("BR-011", &category_field, FixAction::Add, &allowed_categories));
+                    continue;
+                }
+                Some(category) if !allowed_categories.iter().any(|c| c == category) => {
+                    report.add_finding(
+                        ExplainableError::new("BR-010", Severity::Error, Message::new("BR-010").with("category", category).with("invoice_type", &invoice_type), Some(&category_field), Some(category))
+                            .with_allowed_values(&allowed_categories)
+                    );
+                    report.add_suggestion(Self::allowed_values_hint("BR-010", &category_field, FixAction::Replace, &allowed_categories));
+                    continue;
+                }
+                Some(category) => category,
+            };
+
+            // BR-CLS-PAIR: the type must be one of those allowed with the category
+            let allowed_types = matrix.allowed_types(&invoice_type, &vat_categories, category);
+            let found = classification.classification_type.as_deref().map(str::trim).filter(|t| !t.is_empty());
+            let message = match found {
+                None if !allowed_types.is_empty() => Message::new("BR-CLS-PAIR.missing"),
+                Some(found) if !allowed_types.iter().any(|t| t == found) => Message::new("BR-CLS-PAIR.invalid").with("type", found),
+                _ => continue,
+            };
+            let message = message.with("category", category).with("invoice_type", &invoice_type);
+
+            let mut finding = ExplainableError::new("BR-CLS-PAIR", Severity::Error, message, Some(&type_field), found);
+            if !allowed_types.is_empty() {
+                finding = finding.with_allowed_values(&allowed_types);
             }
+            report.add_finding(finding);
+
+            let hint = if allowed_types.is_empty() {
+                // Categories such as category1_95 take no type at all
+                FixHint::new("BR-CLS-PAIR", &type_field, FixAction::Replace, Message::new("hint.BR-CLS-PAIR.none")).with_proposed("")
+            } else {
+                Self::allowed_values_hint("BR-CLS-PAIR", &type_field, if found.is_some() { FixAction::Replace } else { FixAction::Add }, &allowed_types)
+            };
+            report.add_suggestion(hint);
         }
     }
 
@@ -125,23 +318,36 @@ impl BusinessRules {
         // If there are no classifications, check if net value is > 0.
         // Some invoices might not require classification (e.g. retail sometimes), but generally they do.
         // For now, we enforce consistency if any classification exists OR if we want to be strict.
-        
-        let total_classification_amount: Decimal = invoice.income_classifications.iter()
-            .map(|c| c.amount)
-            .sum();
+
+        // Expense records are classified on the buyer's (issuer's) expense side
+        let (amounts, field, key): (Vec<Decimal>, _, _) = if invoice.header.invoice_type.is_expense_record() {
+            (invoice.expense_classifications.iter().map(|c| c.amount).collect(), "expensesClassification", "BR-CLS-TOTAL.expense")
+        } else {
+            (invoice.income_classifications.iter().map(|c| c.amount).collect(), "incomeClassification", "BR-CLS-TOTAL")
+        };
+        let total_classification_amount: Decimal = amounts.iter().sum();
 
         let diff = (total_classification_amount - invoice.totals.total_net_amount).abs();
 
         if diff > dec!(0.05) {
              report.add_error(
                 "BR-CLS-TOTAL", 
-                &format!(
-                    "Income Classification Total ({}) mismatch with Net Value ({})", 
-                    total_classification_amount, invoice.totals.total_net_amount
-                ),
-                Some("invoiceSummary.incomeClassification"),
+                Message::new(key)
+                    .with("classified", total_classification_amount)
+                    .with("net", invoice.totals.total_net_amount),
+                Some(&format!("invoiceSummary.{}", field)),
                 Some(&total_classification_amount.to_string())
             );
+
+            // A single classification can simply take the net value; several need a human to split it
+            let expected = invoice.totals.total_net_amount;
+            let hint = if amounts.len() == 1 {
+                FixHint::new("BR-CLS-TOTAL", &format!("{}[1].amount", field), FixAction::Replace, Message::new("hint.BR-CLS-TOTAL.single"))
+                    .with_proposed(expected)
+            } else {
+                FixHint::new("BR-CLS-TOTAL", field, FixAction::Review, Message::new("hint.BR-CLS-TOTAL.split"))
+            };
+            report.add_suggestion(hint.with_expected(expected));
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,149 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use crate::domain::enums::InvoiceType;
+use crate::domain::invoice::Invoice;
+use crate::i18n::catalog::Message;
+use super::result::{FixAction, FixHint, ValidationReport};
+
+const FIELD: &str = "invoiceHeader.correlatedInvoices";
+
+/// What the checks need to know about an invoice referenced through correlatedInvoices,
+/// taken from the same document or from the validation log
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReferencedInvoice {
+    pub mark: String,
+    pub counterpart_vat: Option<String>,
+    pub total_net_amount: Decimal,
+    pub total_vat_amount: Decimal,
+    pub vat_categories: Vec<String>, // "1".."8", as logged
+}
+
+impl ReferencedInvoice {
+    /// None when the invoice has no MARK and so cannot be referenced
+    pub fn from_invoice(invoice: &Invoice) -> Option<Self> {
+        let mut vat_categories: Vec<String> = invoice.lines.iter().map(|l| l.vat_category.to_string()).collect();
+        vat_categories.sort();
+        vat_categories.dedup();
+
+        Some(Self {
+            mark: invoice.mark.clone()?,
+            counterpart_vat: invoice.counterpart.as_ref().map(|cp| cp.vat_number.clone()),
+            total_net_amount: invoice.totals.total_net_amount,
+            total_vat_amount: invoice.totals.total_vat_amount,
+            vat_categories,
+        })
+    }
+}
+
+pub struct CorrelationRules;
+
+impl CorrelationRules {
+    /// Requires references on correlated types, then compares the invoice with the referenced ones that are known
+    pub fn check(invoice: &Invoice, known: &[ReferencedInvoice], report: &mut ValidationReport) {
+        let invoice_type = &invoice.header.invoice_type;
+        let marks = &invoice.header.correlated_invoices;
+
+        if marks.is_empty() {
+            if matches!(invoice_type, InvoiceType::CreditNote | InvoiceType::DebitNote) {
+                report.add_error(
+                    "BR-018",
+                    Message::new("BR-018").with("invoice_type", invoice_type),
+                    Some(FIELD),
+                    None,
+                );
+                report.add_suggestion(FixHint::new("BR-018", FIELD, FixAction::Add, Message::new("hint.BR-018")));
+            }
+            return;
+        }
+
+        let referenced: Vec<&ReferencedInvoice> = known.iter().filter(|r| marks.contains(&r.mark)).collect();
+
+        Self::check_counterpart(invoice, &referenced, report);
+        Self::check_vat_categories(invoice, &referenced, report);
+
+        // Amounts can only be compared once every referenced invoice is known
+        let all_known = marks.iter().all(|m| referenced.iter().any(|r| &r.mark == m));
+        if invoice_type.is_credit_note() && all_known {
+            Self::check_credited_amounts(invoice, &referenced, report);
+        }
+    }
+
+    fn check_counterpart(invoice: &Invoice, referenced: &[&ReferencedInvoice], report: &mut ValidationReport) {
+        let Some(cp) = &invoice.counterpart else { return };
+
+        for original in referenced {
+            let Some(original_vat) = &original.counterpart_vat else { continue };
+            if original_vat != &cp.vat_number {
+                report.add_error(
+                    "CORR-001",
+                    Message::new("CORR-001")
+                        .with("mark", &original.mark)
+                        .with("original", original_vat)
+                        .with("found", &cp.vat_number),
+                    Some("counterpart.vatNumber"),
+                    Some(&cp.vat_number),
+                );
+                report.add_suggestion(
+                    FixHint::new("CORR-001", "counterpart.vatNumber", FixAction::Replace, Message::new("hint.CORR-001"))
+                        .with_proposed(original_vat)
+                );
+            }
+        }
+    }
+
+    fn check_credited_amounts(invoice: &Invoice, referenced: &[&ReferencedInvoice], report: &mut ValidationReport) {
+        // Credit notes may carry their amounts with either sign
+        let original_net: Decimal = referenced.iter().map(|r| r.total_net_amount.abs()).sum();
+        let original_vat: Decimal = referenced.iter().map(|r| r.total_vat_amount.abs()).sum();
+        let credited_net = invoice.totals.total_net_amount.abs();
+        let credited_vat = invoice.totals.total_vat_amount.abs();
+
+        for (field, credited, original) in [
+            ("totalNetValue", credited_net, original_net),
+            ("totalVatAmount", credited_vat, original_vat),
+        ] {
+            if credited > original {
+                report.add_error(
+                    "CORR-002",
+                    Message::new("CORR-002").with("credited", credited).with("original", original),
+                    Some(field),
+                    Some(&credited.to_string()),
+                );
+                report.add_suggestion(
+                    FixHint::new("CORR-002", field, FixAction::Review, Message::new("hint.CORR-002"))
+                        .with_expected(original)
+                );
+            }
+        }
+    }
+
+    fn check_vat_categories(invoice: &Invoice, referenced: &[&ReferencedInvoice], report: &mut ValidationReport) {
+        if referenced.is_empty() {
+            return;
+        }
+
+        let mut allowed: Vec<String> = referenced.iter().flat_map(|r| r.vat_categories.iter().cloned()).collect();
+        allowed.sort();
+        allowed.dedup();
+
+        for (idx, line) in invoice.lines.iter().enumerate() {
+            let category = line.vat_category.to_string();
+            if !allowed.contains(&category) {
+                let field = format!("line[{}].vatCategory", idx + 1);
+                report.add_warning(
+                    "CORR-003",
+                    Message::new("CORR-003")
+                        .with("line", idx + 1)
+                        .with("category", &category)
+                        .with("allowed", allowed.join(", ")),
+                    Some(&field),
+                    Some(&category),
+                );
+                report.add_suggestion(
+                    FixHint::new("CORR-003", &field, FixAction::Review, Message::new("hint.allowed_values").with("allowed", allowed.join(", ")))
+                        .with_allowed_values(&allowed)
+                );
+            }
+        }
+    }
+}
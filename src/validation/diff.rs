@@ -1,20 +1,71 @@
 use crate::domain::invoice::Invoice;
+use super::diff_policy::{ChangeSeverity, DiffPolicy, DiffVerdict, RecommendedAction};
+use super::line_alignment::{self, LineAlignment, LineMatch};
+use chrono::{NaiveDate, NaiveTime};
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use serde::ser::{self, Serializer};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::any::type_name;
+use std::fmt;
+use std::str::FromStr;
+
+/// A leaf of the invoice, typed so that "100" and "100.00" compare equal and clients need not parse strings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum DiffValue {
+    Bool(bool),
+    Integer(i64),
+    Decimal(Decimal),
+    Date(NaiveDate),
+    Time(NaiveTime),
+    Enum(String), // The myDATA code, e.g. "1.1" or "7"
+    Text(String),
+}
+
+impl fmt::Display for DiffValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DiffValue::Bool(v) => write!(f, "{}", v),
+            DiffValue::Integer(v) => write!(f, "{}", v),
+            DiffValue::Decimal(v) => write!(f, "{}", v),
+            DiffValue::Date(v) => write!(f, "{}", v),
+            DiffValue::Time(v) => write!(f, "{}", v),
+            DiffValue::Enum(v) | DiffValue::Text(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,    // Absent (or null) before
+    Removed,  // Absent (or null) now
+    Modified,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FieldChange {
+    // Serde names of the domain Invoice, lists 1-based: "lines[2].vat_amount". Lines are aligned by content
+    // and addressed by their corrected position; removed lines by their original one, negated ("lines[-2]"),
+    // so they never share a path with the line now at that position.
     pub path: String,
-    pub old_value: String,
-    pub new_value: String,
+    pub kind: ChangeKind,
+    pub old_value: Option<DiffValue>,
+    pub new_value: Option<DiffValue>,
+    pub severity: ChangeSeverity,    // Set by the diff policy
+    pub action: RecommendedAction,
 }
 
 impl FieldChange {
-    fn new(path: &str, old: String, new: String) -> Self {
+    fn new(path: &str, kind: ChangeKind, old_value: Option<DiffValue>, new_value: Option<DiffValue>) -> Self {
         Self {
             path: path.to_string(),
-            old_value: old,
-            new_value: new,
+            kind,
+            old_value,
+            new_value,
+            severity: ChangeSeverity::default(),
+            action: RecommendedAction::default(),
         }
     }
 }
@@ -22,108 +73,556 @@ impl FieldChange {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InvoiceDiff {
     pub has_changes: bool,
+    pub verdict: DiffVerdict, // What the heaviest change requires
     pub changes: Vec<FieldChange>,
+    pub line_matches: Vec<LineMatch>, // Moves and renumbering are reported here, not as changes
 }
 
 impl InvoiceDiff {
     pub fn empty() -> Self {
         Self {
             has_changes: false,
+            verdict: DiffVerdict::Unchanged,
             changes: vec![],
+            line_matches: vec![],
         }
     }
 }
 
+/// Compares every field of the two invoices under the embedded diff policy
 pub fn compare(old: &Invoice, new: &Invoice) -> InvoiceDiff {
+    compare_with_policy(old, new, DiffPolicy::get())
+}
+
+/// The walk goes through the Serialize impl of the invoices,
+/// so a field added to the domain model is diffed without touching this module
+pub fn compare_with_policy(old: &Invoice, new: &Invoice, policy: &DiffPolicy) -> InvoiceDiff {
     let mut changes = Vec::new();
+    // Serializing the domain model into the tree cannot fail, it has no maps with non-scalar keys
+    let mut old_tree = to_node(old).expect("Invoice is always representable as a diff tree");
+    let mut new_tree = to_node(new).expect("Invoice is always representable as a diff tree");
+
+    // Lines are paired by content, the rest of the tree by position
+    let old_lines = take_field(&mut old_tree, "lines");
+    let new_lines = take_field(&mut new_tree, "lines");
+    walk("", Some(&old_tree), Some(&new_tree), &mut changes);
+    let alignment = line_alignment::align(&old.lines, &new.lines);
+    walk_lines(&alignment, &old_lines, &new_lines, &mut changes);
+
+    for change in &mut changes {
+        let outcome = policy.classify(change);
+        change.severity = outcome.severity;
+        change.action = outcome.action;
+    }
+    let reordered = alignment.matches.iter().any(|m| m.moved || m.renumbered);
+    let verdict = changes.iter()
+        .map(|c| DiffVerdict::from(c.action))
+        .chain(reordered.then_some(DiffVerdict::Cosmetic))
+        .max()
+        .unwrap_or(DiffVerdict::Unchanged);
+
+    InvoiceDiff {
+        has_changes: !changes.is_empty() || reordered,
+        verdict,
+        changes,
+        line_matches: alignment.matches,
+    }
+}
+
+/// How an invoice is recognised across two versions of a document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InvoiceRef {
+    pub uid: Option<String>,
+    pub series: String,
+    pub aa: String,
+}
+
+impl InvoiceRef {
+    fn of(invoice: &Invoice) -> Self {
+        Self {
+            uid: invoice.uid.clone(),
+            series: invoice.header.series.clone(),
+            aa: invoice.header.aa.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchedBy {
+    Uid,
+    SeriesAa,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvoicePairDiff {
+    pub original: InvoiceRef,
+    pub corrected: InvoiceRef,
+    pub matched_by: MatchedBy,
+    pub diff: InvoiceDiff,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentDiff {
+    pub pairs: Vec<InvoicePairDiff>,
+    pub only_in_original: Vec<InvoiceRef>,
+    pub only_in_corrected: Vec<InvoiceRef>,
+}
+
+/// Pairs the invoices of two versions of a document, by uid where both sides carry one,
+/// then by series/aa, and diffs each pair
+pub fn compare_documents(original: &[Invoice], corrected: &[Invoice]) -> DocumentDiff {
+    let mut taken = vec![false; corrected.len()];
+    let mut matches: Vec<Option<(usize, MatchedBy)>> = vec![None; original.len()];
+
+    // uid first, over the whole document, so a renumbered invoice is not claimed by series/aa
+    for (idx, old) in original.iter().enumerate() {
+        let Some(uid) = &old.uid else { continue };
+        if let Some(pos) = (0..corrected.len()).find(|&i| !taken[i] && corrected[i].uid.as_ref() == Some(uid)) {
+            taken[pos] = true;
+            matches[idx] = Some((pos, MatchedBy::Uid));
+        }
+    }
+    for (idx, old) in original.iter().enumerate() {
+        if matches[idx].is_some() {
+            continue;
+        }
+        let same_number = |new: &Invoice| new.header.series == old.header.series && new.header.aa == old.header.aa;
+        if let Some(pos) = (0..corrected.len()).find(|&i| !taken[i] && same_number(&corrected[i])) {
+            taken[pos] = true;
+            matches[idx] = Some((pos, MatchedBy::SeriesAa));
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let mut only_in_original = Vec::new();
+    for (old, matched) in original.iter().zip(&matches) {
+        match matched {
+            Some((pos, matched_by)) => pairs.push(InvoicePairDiff {
+                original: InvoiceRef::of(old),
+                corrected: InvoiceRef::of(&corrected[*pos]),
+                matched_by: *matched_by,
+                diff: compare(old, &corrected[*pos]),
+            }),
+            None => only_in_original.push(InvoiceRef::of(old)),
+        }
+    }
+    let only_in_corrected = corrected.iter().zip(&taken)
+        .filter(|(_, taken)| !**taken)
+        .map(|(new, _)| InvoiceRef::of(new))
+        .collect();
+
+    DocumentDiff { pairs, only_in_original, only_in_corrected }
+}
+
+// ============================================================
+// Structural walk
+// ============================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Null,
+    Leaf(DiffValue),
+    Struct(Vec<(String, Node)>),
+    List(Vec<Node>),
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() { key.to_string() } else { format!("{}.{}", path, key) }
+}
+
+// Detaches a list field from the tree, leaving null (= absent) in its place
+fn take_field(tree: &mut Node, key: &str) -> Vec<Node> {
+    let Node::Struct(fields) = tree else { return Vec::new() };
+    match fields.iter_mut().find(|(k, _)| k == key).map(|(_, n)| std::mem::replace(n, Node::Null)) {
+        Some(Node::List(items)) => items,
+        _ => Vec::new(),
+    }
+}
 
-    // 1. Header Comparison
-    if old.header.series != new.header.series {
-        changes.push(FieldChange::new("header.series", old.header.series.clone(), new.header.series.clone()));
-    }
-    if old.header.aa != new.header.aa {
-        changes.push(FieldChange::new("header.aa", old.header.aa.clone(), new.header.aa.clone()));
-    }
-    if old.header.issue_date != new.header.issue_date {
-        changes.push(FieldChange::new("header.issue_date", old.header.issue_date.to_string(), new.header.issue_date.to_string()));
-    }
-    if old.header.invoice_type != new.header.invoice_type {
-        changes.push(FieldChange::new("header.invoice_type", old.header.invoice_type.to_string(), new.header.invoice_type.to_string()));
-    }
-
-    // 2. Issuer/Counterpart (High Level)
-    if old.issuer.vat_number != new.issuer.vat_number {
-        changes.push(FieldChange::new("issuer.vat_number", old.issuer.vat_number.clone(), new.issuer.vat_number.clone()));
-    }
-    if let (Some(old_cp), Some(new_cp)) = (&old.counterpart, &new.counterpart) {
-        if old_cp.vat_number != new_cp.vat_number {
-            changes.push(FieldChange::new("counterpart.vat_number", old_cp.vat_number.clone(), new_cp.vat_number.clone()));
-        }
-    } else if old.counterpart.is_some() != new.counterpart.is_some() {
-        changes.push(FieldChange::new("counterpart", 
-            if old.counterpart.is_some() { "Present".into() } else { "None".into() },
-            if new.counterpart.is_some() { "Present".into() } else { "None".into() }
-        ));
-    }
-
-    // 3. Totals Comparison
-    if old.totals.total_net_amount != new.totals.total_net_amount {
-        changes.push(FieldChange::new("totals.net_amount", old.totals.total_net_amount.to_string(), new.totals.total_net_amount.to_string()));
-    }
-    if old.totals.total_vat_amount != new.totals.total_vat_amount {
-        changes.push(FieldChange::new("totals.vat_amount", old.totals.total_vat_amount.to_string(), new.totals.total_vat_amount.to_string()));
-    }
-    if old.totals.total_gross_amount != new.totals.total_gross_amount {
-        changes.push(FieldChange::new("totals.gross_amount", old.totals.total_gross_amount.to_string(), new.totals.total_gross_amount.to_string()));
-    }
-
-    // 4. Smart Line Comparison (Match by line_number)
-    let old_lines: HashMap<i32, &crate::domain::invoice::InvoiceLine> = old.lines.iter().map(|l| (l.line_number, l)).collect();
-    let new_lines: HashMap<i32, &crate::domain::invoice::InvoiceLine> = new.lines.iter().map(|l| (l.line_number, l)).collect();
-
-    // Check for modifications and deletions
-    for (num, old_line) in &old_lines {
-        match new_lines.get(num) {
-            Some(new_line) => {
-                // Compare critical line fields
-                if old_line.net_value != new_line.net_value {
-                    changes.push(FieldChange::new(
-                        &format!("line[{}].net_value", num),
-                        old_line.net_value.to_string(),
-                        new_line.net_value.to_string()
-                    ));
-                }
-                if old_line.vat_category != new_line.vat_category {
-                    changes.push(FieldChange::new(
-                        &format!("line[{}].vat_category", num),
-                        old_line.vat_category.to_string(),
-                        new_line.vat_category.to_string()
-                    ));
-                }
-            },
-            None => {
-                changes.push(FieldChange::new(
-                    &format!("line[{}]", num),
-                    "Exists".into(),
-                    "Deleted".into()
-                ));
+fn walk_lines(alignment: &LineAlignment, old: &[Node], new: &[Node], changes: &mut Vec<FieldChange>) {
+    for m in &alignment.matches {
+        // Renumbering is on the match, only the content is compared
+        let mut a = old[m.original_position - 1].clone();
+        let mut b = new[m.corrected_position - 1].clone();
+        take_field(&mut a, "line_number");
+        take_field(&mut b, "line_number");
+        walk(&format!("lines[{}]", m.corrected_position), Some(&a), Some(&b), changes);
+    }
+    for &pos in &alignment.removed {
+        leaves(&format!("lines[-{}]", pos), &old[pos - 1], ChangeKind::Removed, changes);
+    }
+    for &pos in &alignment.added {
+        leaves(&format!("lines[{}]", pos), &new[pos - 1], ChangeKind::Added, changes);
+    }
+}
+
+fn walk(path: &str, old: Option<&Node>, new: Option<&Node>, changes: &mut Vec<FieldChange>) {
+    // Null and absent are the same thing: an Option that is not set
+    let old = old.filter(|n| **n != Node::Null);
+    let new = new.filter(|n| **n != Node::Null);
+
+    match (old, new) {
+        (None, None) => {}
+        (Some(Node::Struct(a)), Some(Node::Struct(b))) => {
+            for (key, node) in a {
+                let other = b.iter().find(|(k, _)| k == key).map(|(_, n)| n);
+                walk(&join(path, key), Some(node), other, changes);
+            }
+            for (key, node) in b.iter().filter(|(k, _)| !a.iter().any(|(old_key, _)| old_key == k)) {
+                walk(&join(path, key), None, Some(node), changes);
+            }
+        }
+        (Some(Node::List(a)), Some(Node::List(b))) => {
+            for idx in 0..a.len().max(b.len()) {
+                walk(&format!("{}[{}]", path, idx + 1), a.get(idx), b.get(idx), changes);
+            }
+        }
+        (Some(Node::Leaf(a)), Some(Node::Leaf(b))) => {
+            if a != b {
+                changes.push(FieldChange::new(path, ChangeKind::Modified, Some(a.clone()), Some(b.clone())));
+            }
+        }
+        // Shapes differ (e.g. a counterpart appeared): everything under one side goes, the other comes
+        (old, new) => {
+            if let Some(node) = old {
+                leaves(path, node, ChangeKind::Removed, changes);
+            }
+            if let Some(node) = new {
+                leaves(path, node, ChangeKind::Added, changes);
             }
         }
     }
+}
 
-    // Check for additions
-    for num in new_lines.keys() {
-        if !old_lines.contains_key(num) {
-             changes.push(FieldChange::new(
-                    &format!("line[{}]", num),
-                    "None".into(),
-                    "Created".into()
-                ));
+fn leaves(path: &str, node: &Node, kind: ChangeKind, changes: &mut Vec<FieldChange>) {
+    match node {
+        Node::Null => {}
+        Node::Leaf(value) => {
+            let (old_value, new_value) = match kind {
+                ChangeKind::Removed => (Some(value.clone()), None),
+                _ => (None, Some(value.clone())),
+            };
+            changes.push(FieldChange::new(path, kind, old_value, new_value));
+        }
+        Node::Struct(fields) => {
+            for (key, child) in fields {
+                leaves(&join(path, key), child, kind, changes);
+            }
+        }
+        Node::List(items) => {
+            for (idx, child) in items.iter().enumerate() {
+                leaves(&format!("{}[{}]", path, idx + 1), child, kind, changes);
+            }
         }
     }
+}
 
-    InvoiceDiff {
-        has_changes: !changes.is_empty(),
-        changes,
+// ============================================================
+// Serializer into the diff tree
+// ============================================================
+
+#[derive(Debug)]
+struct TreeError(String);
+
+impl fmt::Display for TreeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for TreeError {}
+
+impl ser::Error for TreeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        TreeError(msg.to_string())
+    }
+}
+
+/// Serializes a value into the tree. Decimals and dates serialize as plain strings,
+/// so their type is recovered from the Rust type of the value being serialized.
+fn to_node<T: ?Sized + Serialize>(value: &T) -> Result<Node, TreeError> {
+    match value.serialize(TreeSerializer)? {
+        Node::Leaf(DiffValue::Text(text)) => Ok(Node::Leaf(retype::<T>(text))),
+        node => Ok(node),
+    }
+}
+
+fn retype<T: ?Sized>(text: String) -> DiffValue {
+    let name = type_name::<T>();
+    let typed = if name == type_name::<Decimal>() {
+        Decimal::from_str(&text).ok().map(DiffValue::Decimal)
+    } else if name == type_name::<NaiveDate>() {
+        NaiveDate::from_str(&text).ok().map(DiffValue::Date)
+    } else if name == type_name::<NaiveTime>() {
+        NaiveTime::from_str(&text).ok().map(DiffValue::Time)
+    } else {
+        None
+    };
+    typed.unwrap_or(DiffValue::Text(text))
+}
+
+struct TreeSerializer;
+
+struct ListBuilder(Vec<Node>);
+
+struct StructBuilder {
+    fields: Vec<(String, Node)>,
+    pending_key: Option<String>,
+}
+
+struct VariantBuilder<B> {
+    variant: &'static str,
+    inner: B,
+}
+
+impl Serializer for TreeSerializer {
+    type Ok = Node;
+    type Error = TreeError;
+    type SerializeSeq = ListBuilder;
+    type SerializeTuple = ListBuilder;
+    type SerializeTupleStruct = ListBuilder;
+    type SerializeTupleVariant = VariantBuilder<ListBuilder>;
+    type SerializeMap = StructBuilder;
+    type SerializeStruct = StructBuilder;
+    type SerializeStructVariant = VariantBuilder<StructBuilder>;
+
+    fn serialize_bool(self, v: bool) -> Result<Node, TreeError> {
+        Ok(Node::Leaf(DiffValue::Bool(v)))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Node, TreeError> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Node, TreeError> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Node, TreeError> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Node, TreeError> {
+        Ok(Node::Leaf(DiffValue::Integer(v)))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Node, TreeError> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Node, TreeError> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Node, TreeError> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Node, TreeError> {
+        i64::try_from(v)
+            .map(|v| Node::Leaf(DiffValue::Integer(v)))
+            .or_else(|_| self.serialize_str(&v.to_string()))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Node, TreeError> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Node, TreeError> {
+        match Decimal::from_f64(v) {
+            Some(d) => Ok(Node::Leaf(DiffValue::Decimal(d))),
+            None => self.serialize_str(&v.to_string()),
+        }
+    }
+
+    fn serialize_char(self, v: char) -> Result<Node, TreeError> {
+        self.serialize_str(&v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Node, TreeError> {
+        Ok(Node::Leaf(DiffValue::Text(v.to_string())))
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<Node, TreeError> {
+        Err(ser::Error::custom("byte fields are not diffable"))
+    }
+
+    fn serialize_none(self) -> Result<Node, TreeError> {
+        Ok(Node::Null)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Node, TreeError> {
+        to_node(value)
+    }
+
+    fn serialize_unit(self) -> Result<Node, TreeError> {
+        Ok(Node::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Node, TreeError> {
+        Ok(Node::Null)
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<Node, TreeError> {
+        Ok(Node::Leaf(DiffValue::Enum(variant.to_string())))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<Node, TreeError> {
+        to_node(value)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Node, TreeError> {
+        Ok(Node::Struct(vec![(variant.to_string(), to_node(value)?)]))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<ListBuilder, TreeError> {
+        Ok(ListBuilder(Vec::with_capacity(len.unwrap_or(0))))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<ListBuilder, TreeError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<ListBuilder, TreeError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<VariantBuilder<ListBuilder>, TreeError> {
+        Ok(VariantBuilder { variant, inner: ListBuilder(Vec::with_capacity(len)) })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<StructBuilder, TreeError> {
+        Ok(StructBuilder { fields: Vec::new(), pending_key: None })
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<StructBuilder, TreeError> {
+        self.serialize_map(None)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<VariantBuilder<StructBuilder>, TreeError> {
+        Ok(VariantBuilder { variant, inner: StructBuilder { fields: Vec::new(), pending_key: None } })
+    }
+}
+
+impl ser::SerializeSeq for ListBuilder {
+    type Ok = Node;
+    type Error = TreeError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), TreeError> {
+        self.0.push(to_node(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Node, TreeError> {
+        Ok(Node::List(self.0))
+    }
+}
+
+impl ser::SerializeTuple for ListBuilder {
+    type Ok = Node;
+    type Error = TreeError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), TreeError> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Node, TreeError> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for ListBuilder {
+    type Ok = Node;
+    type Error = TreeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), TreeError> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Node, TreeError> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleVariant for VariantBuilder<ListBuilder> {
+    type Ok = Node;
+    type Error = TreeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), TreeError> {
+        ser::SerializeSeq::serialize_element(&mut self.inner, value)
+    }
+
+    fn end(self) -> Result<Node, TreeError> {
+        Ok(Node::Struct(vec![(self.variant.to_string(), ser::SerializeSeq::end(self.inner)?)]))
+    }
+}
+
+impl ser::SerializeMap for StructBuilder {
+    type Ok = Node;
+    type Error = TreeError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), TreeError> {
+        let key = match to_node(key)? {
+            Node::Leaf(value) => value.to_string(),
+            _ => return Err(ser::Error::custom("map keys must be scalars")),
+        };
+        self.pending_key = Some(key);
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), TreeError> {
+        let key = self.pending_key.take().ok_or_else(|| ser::Error::custom("map value without a key"))?;
+        self.fields.push((key, to_node(value)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Node, TreeError> {
+        Ok(Node::Struct(self.fields))
+    }
+}
+
+impl ser::SerializeStruct for StructBuilder {
+    type Ok = Node;
+    type Error = TreeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), TreeError> {
+        self.fields.push((key.to_string(), to_node(value)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Node, TreeError> {
+        Ok(Node::Struct(self.fields))
+    }
+}
+
+impl ser::SerializeStructVariant for VariantBuilder<StructBuilder> {
+    type Ok = Node;
+    type Error = TreeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), TreeError> {
+        ser::SerializeStruct::serialize_field(&mut self.inner, key, value)
+    }
+
+    fn end(self) -> Result<Node, TreeError> {
+        Ok(Node::Struct(vec![(self.variant.to_string(), ser::SerializeStruct::end(self.inner)?)]))
     }
 }
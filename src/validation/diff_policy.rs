@@ -0,0 +1,132 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use super::diff::{DiffValue, FieldChange};
+
+// Embed the default policy into the binary
+const POLICY_YAML: &str = include_str!("../../data/diff_policy.yaml");
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeSeverity {
+    #[default]
+    Info,
+    Warning,
+    Critical,
+}
+
+/// What has to be sent to myDATA for the change to take effect, from the lightest to the heaviest
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecommendedAction {
+    #[default]
+    None,
+    ResendDetails,    // Classifications / payment methods for the same MARK
+    CancelAndReissue,
+    CreditNote,       // 5.1 / 5.2 against the original
+}
+
+/// Overall outcome of a diff, driven by its heaviest action
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffVerdict {
+    #[default]
+    Unchanged,
+    Cosmetic,
+    Correctable,
+    RequiresCancellation,
+    RequiresCreditNote,
+}
+
+impl From<RecommendedAction> for DiffVerdict {
+    fn from(action: RecommendedAction) -> Self {
+        match action {
+            RecommendedAction::None => DiffVerdict::Cosmetic,
+            RecommendedAction::ResendDetails => DiffVerdict::Correctable,
+            RecommendedAction::CancelAndReissue => DiffVerdict::RequiresCancellation,
+            RecommendedAction::CreditNote => DiffVerdict::RequiresCreditNote,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct PolicyOutcome {
+    pub severity: ChangeSeverity,
+    pub action: RecommendedAction,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PolicyRule {
+    pub paths: Vec<String>,
+    #[serde(default)]
+    pub max_delta: Option<Decimal>, // Only decimal changes up to this amount
+    #[serde(flatten)]
+    pub outcome: PolicyOutcome,
+}
+
+impl PolicyRule {
+    fn matches(&self, change: &FieldChange) -> bool {
+        if !self.paths.iter().any(|p| path_matches(p, &change.path)) {
+            return false;
+        }
+        match self.max_delta {
+            None => true,
+            Some(max) => match (&change.old_value, &change.new_value) {
+                (Some(DiffValue::Decimal(old)), Some(DiffValue::Decimal(new))) => (new - old).abs() <= max,
+                _ => false,
+            },
+        }
+    }
+}
+
+/// Ordered table deciding what each field change means; the first matching rule wins
+#[derive(Debug, Clone, Deserialize)]
+pub struct DiffPolicy {
+    rules: Vec<PolicyRule>,
+    default: PolicyOutcome,
+}
+
+impl DiffPolicy {
+    pub fn load_from_yaml(content: &str) -> Result<Self, serde_yaml::Error> {
+        serde_yaml::from_str(content)
+    }
+
+    /// The embedded policy, parsed on first use
+    pub fn get() -> &'static DiffPolicy {
+        static POLICY: OnceLock<DiffPolicy> = OnceLock::new();
+        POLICY.get_or_init(|| Self::load_from_yaml(POLICY_YAML).expect("Embedded diff policy is invalid"))
+    }
+
+    pub fn classify(&self, change: &FieldChange) -> PolicyOutcome {
+        self.rules.iter()
+            .find(|r| r.matches(change))
+            .map(|r| r.outcome)
+            .unwrap_or(self.default)
+    }
+}
+
+// "lines[3].net_value" against "lines[*].net_value", "lines[*].**", "totals.*"
+fn path_matches(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split('.').collect();
+    let path: Vec<String> = path.split('.').map(any_index).collect();
+
+    let mut segments = path.iter();
+    for (idx, expected) in pattern.iter().enumerate() {
+        if *expected == "**" && idx == pattern.len() - 1 {
+            return segments.next().is_some();
+        }
+        match segments.next() {
+            Some(segment) if *expected == "*" || expected == segment => {}
+            _ => return false,
+        }
+    }
+    segments.next().is_none()
+}
+
+// "lines[3]" -> "lines[*]"
+fn any_index(segment: &str) -> String {
+    match segment.find('[') {
+        Some(pos) if segment.ends_with(']') => format!("{}[*]", &segment[..pos]),
+        _ => segment.to_string(),
+    }
+}
@@ -0,0 +1,76 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use crate::domain::invoice::Invoice;
+use crate::i18n::catalog::Message;
+use super::book_rules::BookRules;
+use super::result::{FixAction, FixHint, ValidationReport};
+
+/// What the validation log knows about the invoice's series, loaded only in stateful mode
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SeriesHistory {
+    pub same_number_hashes: Vec<String>, // Content hashes logged earlier under the same issuer/series/aa
+    pub last_aa: Option<u64>,            // Highest numeric aa validated so far in the series
+    pub last_date: Option<NaiveDate>,    // Issue date of that invoice
+}
+
+// Compares the invoice with earlier validations of its series. Findings are warnings only:
+// the log also holds drafts and failed attempts, so history can suggest but not prove a problem.
+pub struct HistoryRules;
+
+impl HistoryRules {
+    pub fn check(invoice: &Invoice, history: &SeriesHistory, report: &mut ValidationReport) {
+        let series = &invoice.header.series;
+        let aa = &invoice.header.aa;
+
+        // Re-validating a known number: only its content matters
+        if !history.same_number_hashes.is_empty() {
+            let fingerprint = BookRules::content_fingerprint(invoice);
+            if !history.same_number_hashes.contains(&fingerprint) {
+                report.add_warning(
+                    "HIST-001",
+                    Message::new("HIST-001").with("series", series).with("aa", aa),
+                    Some("invoiceHeader.aa"),
+                    Some(aa),
+                );
+                report.add_suggestion(FixHint::new("HIST-001", "invoiceHeader.aa", FixAction::Review, Message::new("hint.HIST-001")));
+            }
+            return;
+        }
+
+        let (Ok(number), Some(last_aa)) = (aa.trim().parse::<u64>(), history.last_aa) else { return };
+        if number <= last_aa {
+            return;
+        }
+
+        if let Some(last_date) = history.last_date {
+            if invoice.header.issue_date < last_date {
+                report.add_warning(
+                    "HIST-002",
+                    Message::new("HIST-002")
+                        .with("series", series)
+                        .with("date", invoice.header.issue_date)
+                        .with("last_aa", last_aa)
+                        .with("last_date", last_date),
+                    Some("invoiceHeader.issueDate"),
+                    Some(&invoice.header.issue_date.to_string()),
+                );
+            }
+        }
+
+        if number > last_aa + 1 {
+            report.add_warning(
+                "HIST-003",
+                Message::new("HIST-003")
+                    .with("series", series)
+                    .with("aa", number)
+                    .with("last_aa", last_aa),
+                Some("invoiceHeader.aa"),
+                Some(aa),
+            );
+            report.add_suggestion(
+                FixHint::new("HIST-003", "invoiceHeader.aa", FixAction::Review, Message::new("hint.HIST-003"))
+                    .with_expected(last_aa + 1)
+            );
+        }
+    }
+}
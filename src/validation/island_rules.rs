@@ -0,0 +1,84 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use crate::domain::invoice::Invoice;
+use crate::i18n::catalog::Message;
+use super::result::{ExplainableError, FixAction, FixHint, Severity, ValidationReport};
+
+/// A branch registered on an Aegean island with reduced VAT rates, for a period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IslandBranch {
+    pub vat_number: String,
+    pub branch: i32,
+    pub island: String,
+    pub valid_from: NaiveDate,
+    pub valid_until: Option<NaiveDate>, // None = still eligible
+}
+
+impl IslandBranch {
+    pub fn covers(&self, date: NaiveDate) -> bool {
+        self.valid_from <= date && self.valid_until.is_none_or(|until| date <= until)
+    }
+
+    /// Why the registration cannot be stored, if it cannot
+    pub fn registration_error(&self) -> Option<&'static str> {
+        if self.vat_number.trim().is_empty() {
+            Some("vat_number is required")
+        } else if self.island.trim().is_empty() {
+            Some("island is required")
+        } else if self.branch < 0 {
+            Some("branch cannot be negative")
+        } else if self.valid_until.is_some_and(|until| until < self.valid_from) {
+            Some("valid_until is before valid_from")
+        } else {
+            None
+        }
+    }
+}
+
+// The reduced island rates (17%, 9%, 4%) are only allowed to branches registered on an eligible
+// island on the issue date. The seller's branch counts, also when the buyer issued the document.
+pub struct IslandRules;
+
+impl IslandRules {
+    pub fn check(invoice: &Invoice, registry: &[IslandBranch], report: &mut ValidationReport) {
+        let Some(seller) = invoice.seller() else { return };
+        let date = invoice.header.issue_date;
+
+        let entries: Vec<&IslandBranch> = registry.iter()
+            .filter(|b| b.vat_number == seller.vat_number && b.branch == seller.branch)
+            .collect();
+        if entries.iter().any(|b| b.covers(date)) {
+            return;
+        }
+
+        for (idx, line) in invoice.lines.iter().enumerate() {
+            let Some(mainland) = line.vat_category.mainland_equivalent() else { continue };
+
+            // A registration outside the issue date is worth naming, the fix may be the date
+            let message = match entries.first() {
+                Some(entry) => Message::new("ISL-001.period")
+                    .with("island", &entry.island)
+                    .with("from", entry.valid_from)
+                    .with("until", entry.valid_until.map(|d| d.to_string()).unwrap_or_else(|| "-".to_string())),
+                None => Message::new("ISL-001"),
+            };
+            let message = message
+                .with("line", idx + 1)
+                .with("category", line.vat_category)
+                .with("vat", seller.vat_number)
+                .with("branch", seller.branch)
+                .with("date", date);
+
+            let field = format!("line[{}].vat_category", idx + 1);
+            let category = line.vat_category.to_string();
+            report.add_finding(
+                ExplainableError::new("ISL-001", Severity::Error, message, Some(&field), Some(&category))
+                    .with_allowed_values(&[mainland.to_string()])
+            );
+            report.add_suggestion(
+                FixHint::new("ISL-001", &field, FixAction::Replace, Message::new("hint.ISL-001").with("category", mainland))
+                    .with_proposed(mainland)
+            );
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{HashMap, VecDeque};
+use crate::domain::enums::VatCategory;
+use crate::domain::invoice::InvoiceLine;
+
+// Below this similarity two lines are a removal and an addition, not a changed line
+const MATCH_THRESHOLD: f64 = 0.5;
+
+// Weights of the similarity components, they add up to 1
+const WEIGHT_DESCRIPTION: f64 = 0.3;
+const WEIGHT_NET_VALUE: f64 = 0.35;
+const WEIGHT_VAT_AMOUNT: f64 = 0.15;
+const WEIGHT_VAT_CATEGORY: f64 = 0.2;
+
+/// A line of the original paired with a line of the corrected invoice; positions are 1-based
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LineMatch {
+    pub original_position: usize,
+    pub corrected_position: usize,
+    pub original_line_number: i32,
+    pub corrected_line_number: i32,
+    pub confidence: f64,  // Content similarity, 0..1
+    pub moved: bool,      // Out of order relative to the other matched lines
+    pub renumbered: bool, // lineNumber differs, content may not
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LineAlignment {
+    pub matches: Vec<LineMatch>,
+    pub removed: Vec<usize>, // Positions in the original
+    pub added: Vec<usize>,   // Positions in the corrected invoice
+}
+
+/// Pairs lines by content rather than by lineNumber, so an inserted line does not shift every later one.
+/// Identical lines are anchored first, in order; the rest are scored pairwise, best pairs first,
+/// equal scores going to the closest positions.
+pub fn align(original: &[InvoiceLine], corrected: &[InvoiceLine]) -> LineAlignment {
+    let old_profiles: Vec<LineProfile> = original.iter().map(LineProfile::of).collect();
+    let new_profiles: Vec<LineProfile> = corrected.iter().map(LineProfile::of).collect();
+
+    let mut used_old = vec![false; original.len()];
+    let mut used_new = vec![false; corrected.len()];
+    let mut pairs = Vec::new();
+
+    // Exact anchors: the k-th copy of a line in the original goes with its k-th copy in the correction
+    let mut copies: HashMap<&ExactKey, VecDeque<usize>> = HashMap::new();
+    for (i, profile) in old_profiles.iter().enumerate() {
+        copies.entry(&profile.key).or_default().push_back(i);
+    }
+    for (j, profile) in new_profiles.iter().enumerate() {
+        if let Some(i) = copies.get_mut(&profile.key).and_then(VecDeque::pop_front) {
+            used_old[i] = true;
+            used_new[j] = true;
+            pairs.push((i, j, 1.0));
+        }
+    }
+
+    let mut candidates = Vec::new();
+    for (i, old) in old_profiles.iter().enumerate().filter(|&(i, _)| !used_old[i]) {
+        for (j, new) in new_profiles.iter().enumerate().filter(|&(j, _)| !used_new[j]) {
+            let score = similarity(old, new);
+            if score >= MATCH_THRESHOLD {
+                candidates.push((score, i.abs_diff(j), i, j));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+
+    for (score, _, i, j) in candidates {
+        if !used_old[i] && !used_new[j] {
+            used_old[i] = true;
+            used_new[j] = true;
+            pairs.push((i, j, score));
+        }
+    }
+    pairs.sort_by_key(|&(i, _, _)| i);
+
+    let in_order = longest_increasing(&pairs.iter().map(|&(_, j, _)| j).collect::<Vec<_>>());
+    let matches = pairs.iter().enumerate()
+        .map(|(k, &(i, j, score))| LineMatch {
+            original_position: i + 1,
+            corrected_position: j + 1,
+            original_line_number: original[i].line_number,
+            corrected_line_number: corrected[j].line_number,
+            confidence: (score * 100.0).round() / 100.0,
+            moved: !in_order[k],
+            renumbered: original[i].line_number != corrected[j].line_number,
+        })
+        .collect();
+
+    LineAlignment {
+        matches,
+        removed: (0..original.len()).filter(|&i| !used_old[i]).map(|i| i + 1).collect(),
+        added: (0..corrected.len()).filter(|&j| !used_new[j]).map(|j| j + 1).collect(),
+    }
+}
+
+// Description words, net value, VAT amount, VAT category; equal keys mean a similarity of 1
+type ExactKey = (Vec<String>, Decimal, Decimal, VatCategory);
+
+// What the similarity needs of a line, worked out once per line rather than once per pair
+struct LineProfile<'a> {
+    key: ExactKey, // Words sorted and deduplicated, amounts normalized
+    line: &'a InvoiceLine,
+}
+
+impl<'a> LineProfile<'a> {
+    fn of(line: &'a InvoiceLine) -> Self {
+        let mut words: Vec<String> = line.description.to_lowercase().split_whitespace().map(str::to_string).collect();
+        words.sort();
+        words.dedup();
+        Self {
+            key: (words, line.net_value.normalize(), line.vat_amount.normalize(), line.vat_category),
+            line,
+        }
+    }
+
+    fn words(&self) -> &[String] {
+        &self.key.0
+    }
+}
+
+fn similarity(a: &LineProfile, b: &LineProfile) -> f64 {
+    let category = if a.line.vat_category == b.line.vat_category { 1.0 } else { 0.0 };
+    WEIGHT_DESCRIPTION * text_similarity(a.words(), b.words())
+        + WEIGHT_NET_VALUE * closeness(a.line.net_value, b.line.net_value)
+        + WEIGHT_VAT_AMOUNT * closeness(a.line.vat_amount, b.line.vat_amount)
+        + WEIGHT_VAT_CATEGORY * category
+}
+
+// 1 for equal amounts, falling linearly to 0 as the difference reaches the larger amount
+fn closeness(a: Decimal, b: Decimal) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+    let scale = a.abs().max(b.abs());
+    let ratio = ((a - b).abs() / scale).to_f64().unwrap_or(1.0);
+    (1.0 - ratio).max(0.0)
+}
+
+// Jaccard similarity of two sorted, deduplicated word lists, counted in one merge pass
+fn text_similarity(a: &[String], b: &[String]) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    let (mut x, mut y, mut common) = (0, 0, 0);
+    while x < a.len() && y < b.len() {
+        match a[x].cmp(&b[y]) {
+            Ordering::Less => x += 1,
+            Ordering::Greater => y += 1,
+            Ordering::Equal => {
+                common += 1;
+                x += 1;
+                y += 1;
+            }
+        }
+    }
+    common as f64 / (a.len() + b.len() - common) as f64
+}
+
+// Marks one longest strictly increasing subsequence (patience sorting, O(n log n));
+// the matches outside it are the moved ones
+fn longest_increasing(values: &[usize]) -> Vec<bool> {
+    let mut tails: Vec<usize> = Vec::new(); // tails[l]: index ending the best subsequence of length l + 1
+    let mut previous: Vec<Option<usize>> = vec![None; values.len()];
+    for (k, &value) in values.iter().enumerate() {
+        let length = tails.partition_point(|&t| values[t] < value);
+        previous[k] = length.checked_sub(1).map(|l| tails[l]);
+        if length == tails.len() {
+            tails.push(k);
+        } else {
+            tails[length] = k;
+        }
+    }
+
+    let mut chain = vec![false; values.len()];
+    let mut cursor = tails.last().copied();
+    while let Some(k) = cursor {
+        chain[k] = true;
+        cursor = previous[k];
+    }
+    chain
+}
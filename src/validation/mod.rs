@@ -3,4 +3,13 @@ pub mod business_rules;
 pub mod mydata_rules;
 pub mod result;
 pub mod rules_engine;
-pub mod diff;
\ No newline at end of file
+pub mod diff;
+pub mod diff_policy;
+pub mod line_alignment;
+pub mod revision;
+pub mod risk;
+pub mod book_rules;
+pub mod history_rules;
+pub mod correlation_rules;
+pub mod island_rules;
+pub mod reverse_charge_rules;
@@ -1,4 +1,8 @@
 use serde::{Deserialize, Serialize};
+use super::risk::RiskFactor;
+use super::revision::RevisionComparison;
+use crate::i18n::catalog::Message;
+use crate::i18n::lang::Lang;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ValidationStatus {
@@ -7,11 +11,56 @@ pub enum ValidationStatus {
     Red,    // Critical errors, invalid
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+impl ValidationStatus {
+    pub fn summary(&self) -> Message {
+        match self {
+            ValidationStatus::Green => Message::new("summary.green"),
+            ValidationStatus::Yellow => Message::new("summary.yellow"),
+            ValidationStatus::Red => Message::new("summary.red"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Severity {
-    Info,
-    Warning,
-    Error,
+    Info,    // Informational note, does not affect status
+    Warning, // Turns the report Yellow
+    Error,   // Turns the report Red
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SeverityCounts {
+    pub info: usize,
+    pub warning: usize,
+    pub error: usize,
+}
+
+impl SeverityCounts {
+    pub fn get(&self, severity: Severity) -> usize {
+        match severity {
+            Severity::Info => self.info,
+            Severity::Warning => self.warning,
+            Severity::Error => self.error,
+        }
+    }
+
+    fn increment(&mut self, severity: Severity) {
+        match severity {
+            Severity::Info => self.info += 1,
+            Severity::Warning => self.warning += 1,
+            Severity::Error => self.error += 1,
+        }
+    }
+
+    pub fn status(&self) -> ValidationStatus {
+        if self.error > 0 {
+            ValidationStatus::Red
+        } else if self.warning > 0 {
+            ValidationStatus::Yellow
+        } else {
+            ValidationStatus::Green
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,21 +71,97 @@ pub struct ExplainableError {
     pub reason: String,
     pub allowed_values: Option<Vec<String>>,
     pub severity: Severity,
+    #[serde(skip)]
+    pub message: Message, // Source of `reason`, kept for re-rendering in another language
+}
+
+impl ExplainableError {
+    pub fn new(
+        code: &str,
+        severity: Severity,
+        message: Message,
+        field: Option<&str>,
+        value: Option<&str>,
+    ) -> Self {
+        Self {
+            code: code.to_string(),
+            field: field.map(|s| s.to_string()),
+            value_found: value.map(|s| s.to_string()),
+            reason: message.render(Lang::default()),
+            allowed_values: None,
+            severity,
+            message,
+        }
+    }
+
+    pub fn with_allowed_values(mut self, allowed_values: &[String]) -> Self {
+        self.allowed_values = Some(allowed_values.to_vec());
+        self
+    }
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum FixAction {
+    Replace, // Set `field` to `proposed_value`
+    Add,     // `field` is missing and must be added
+    Review,  // No safe automatic fix, a human has to decide
+}
+
+/// Machine-readable fix for a finding, precise enough to drive an "apply fix" button
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FixHint {
+    pub code: String, // Finding this hint resolves
     pub field: String,
+    pub action: FixAction,
     pub suggestion: String,
+    pub allowed_values: Option<Vec<String>>,
+    pub expected_value: Option<String>, // Recomputed amount, when the rule can derive one
+    pub proposed_value: Option<String>,
+    #[serde(skip)]
+    pub message: Message,
+}
+
+impl FixHint {
+    pub fn new(code: &str, field: &str, action: FixAction, message: Message) -> Self {
+        Self {
+            code: code.to_string(),
+            field: field.to_string(),
+            action,
+            suggestion: message.render(Lang::default()),
+            allowed_values: None,
+            expected_value: None,
+            proposed_value: None,
+            message,
+        }
+    }
+
+    pub fn with_allowed_values(mut self, allowed_values: &[String]) -> Self {
+        self.allowed_values = Some(allowed_values.to_vec());
+        self
+    }
+
+    pub fn with_expected(mut self, expected: impl ToString) -> Self {
+        self.expected_value = Some(expected.to_string());
+        self
+    }
+
+    pub fn with_proposed(mut self, proposed: impl ToString) -> Self {
+        self.proposed_value = Some(proposed.to_string());
+        self
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationReport {
     pub status: ValidationStatus,
     pub risk_score: u8,
+    pub risk_factors: Vec<RiskFactor>, // How each rule and signal contributed to risk_score
     pub summary: String,
-    pub errors: Vec<ExplainableError>,
+    pub counts: SeverityCounts,
+    pub errors: Vec<ExplainableError>, // All findings, regardless of severity
     pub suggestions: Vec<FixHint>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_attempt: Option<RevisionComparison>, // On request, against the last logged validation
 }
 
 impl Default for ValidationReport {
@@ -44,9 +169,12 @@ impl Default for ValidationReport {
         Self {
             status: ValidationStatus::Green,
             risk_score: 0,
-            summary: "Επιτυχής έλεγχος. Έτοιμο για υποβολή.".to_string(),
+            risk_factors: vec![],
+            summary: ValidationStatus::Green.summary().render(Lang::default()),
+            counts: SeverityCounts::default(),
             errors: vec![],
             suggestions: vec![],
+            previous_attempt: None,
         }
     }
 }
@@ -56,41 +184,67 @@ impl ValidationReport {
         Self::default()
     }
 
-    pub fn add_error(
-        &mut self,
-        code: &str,
-        message: &str,
-        field: Option<&str>,
-        value: Option<&str>,
-    ) {
-        self.status = ValidationStatus::Red;
-        self.risk_score = 100; // Critical error maxes out risk
-        self.summary = "Απέτυχε ο έλεγχος. Διορθώστε τα σφάλματα.".to_string();
-        
-        self.errors.push(ExplainableError {
-            code: code.to_string(),
-            field: field.map(|s| s.to_string()),
-            value_found: value.map(|s| s.to_string()),
-            reason: message.to_string(),
-            allowed_values: None,
-            severity: Severity::Error,
-        });
+    /// Records a finding of any severity and re-derives the status from the counts
+    pub fn add_finding(&mut self, finding: ExplainableError) {
+        self.counts.increment(finding.severity);
+        self.errors.push(finding);
+        self.refresh_status();
+    }
+
+    pub fn add_error(&mut self, code: &str, message: Message, field: Option<&str>, value: Option<&str>) {
+        self.add_finding(ExplainableError::new(code, Severity::Error, message, field, value));
+    }
+
+    pub fn add_warning(&mut self, code: &str, message: Message, field: Option<&str>, value: Option<&str>) {
+        self.add_finding(ExplainableError::new(code, Severity::Warning, message, field, value));
+    }
+
+    pub fn add_info(&mut self, code: &str, message: Message, field: Option<&str>, value: Option<&str>) {
+        self.add_finding(ExplainableError::new(code, Severity::Info, message, field, value));
+    }
+
+    pub fn add_suggestion(&mut self, hint: FixHint) {
+        self.suggestions.push(hint);
     }
 
-    pub fn add_warning(&mut self, code: &str, message: &str) {
-        if self.status == ValidationStatus::Green {
-            self.status = ValidationStatus::Yellow;
-            self.summary = "Προσοχή. Υπάρχουν επισημάνσεις.".to_string();
-            self.risk_score = std::cmp::max(self.risk_score, 30);
+    pub fn findings(&self, severity: Severity) -> impl Iterator<Item = &ExplainableError> {
+        self.errors.iter().filter(move |e| e.severity == severity)
+    }
+
+    /// Re-renders every human-readable text of the report in the given language
+    /// Re-renders the texts in `lang`. Messages are not serialized, so a report read back from
+    /// storage has none and keeps the texts it was stored with
+    pub fn localize(&mut self, lang: Lang) {
+        self.summary = self.status.summary().render(lang);
+        for finding in self.errors.iter_mut().filter(|f| !f.message.key.is_empty()) {
+            finding.reason = finding.message.render(lang);
+        }
+        for hint in self.suggestions.iter_mut().filter(|h| !h.message.key.is_empty()) {
+            hint.suggestion = hint.message.render(lang);
         }
+        for factor in self.risk_factors.iter_mut().filter(|f| !f.message.key.is_empty()) {
+            factor.reason = factor.message.render(lang);
+        }
+    }
 
-        self.errors.push(ExplainableError {
-            code: code.to_string(),
-            field: None,
-            value_found: None,
-            reason: message.to_string(),
-            allowed_values: None,
-            severity: Severity::Warning,
-        });
+    fn refresh_status(&mut self) {
+        self.status = self.counts.status();
+        self.summary = self.status.summary().render(Lang::default());
+    }
+}
+
+/// Result for one XML document: cross-invoice findings next to the per-invoice reports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentReport {
+    pub book: ValidationReport,
+    pub invoices: Vec<ValidationReport>,
+}
+
+impl DocumentReport {
+    pub fn localize(&mut self, lang: Lang) {
+        self.book.localize(lang);
+        for report in &mut self.invoices {
+            report.localize(lang);
+        }
     }
 }
@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+use crate::domain::country::{CountryCatalog, VatRegion};
+use crate::domain::enums::{InvoiceType, VatCategory};
+use crate::domain::invoice::{Invoice, InvoiceLine};
+use crate::i18n::catalog::Message;
+use super::result::{ExplainableError, FixAction, FixHint, Severity, ValidationReport};
+
+// myDATA vatExemptionCategory codes of the articles that shift the VAT to the recipient
+const EXEMPTION_ART_14: i32 = 4; // Intra-community services, place of supply at the recipient
+const EXEMPTION_ART_28: i32 = 14; // Intra-community supplies of goods
+const EXEMPTION_ART_39A: i32 = 16; // Domestic reverse charge (scrap, construction, emission allowances...)
+
+/// Why no VAT is charged on the invoice and the recipient accounts for it instead
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReverseChargeScenario {
+    IntraEuGoods,    // 1.2, art. 28 of the VAT Code
+    IntraEuServices, // 2.2, art. 14 of the VAT Code
+    Domestic,        // Lines under art. 39a, recipient is a Greek business
+}
+
+impl ReverseChargeScenario {
+    /// Recognised from the invoice type, or from the art. 39a exemption on the lines
+    pub fn detect(invoice: &Invoice) -> Option<Self> {
+        match invoice.header.invoice_type {
+            InvoiceType::SalesInvoiceIntra => Some(Self::IntraEuGoods),
+            InvoiceType::ServiceInvoiceIntra => Some(Self::IntraEuServices),
+            _ if invoice.lines.iter().any(|l| l.vat_exemption_category == Some(EXEMPTION_ART_39A)) => Some(Self::Domestic),
+            _ => None,
+        }
+    }
+
+    /// The vatExemptionCategory its zero-rated lines must carry
+    pub fn exemption_category(&self) -> i32 {
+        match self {
+            Self::IntraEuGoods => EXEMPTION_ART_28,
+            Self::IntraEuServices => EXEMPTION_ART_14,
+            Self::Domestic => EXEMPTION_ART_39A,
+        }
+    }
+
+    fn key(&self) -> &'static str {
+        match self {
+            Self::IntraEuGoods => "intra_eu_goods",
+            Self::IntraEuServices => "intra_eu_services",
+            Self::Domestic => "domestic",
+        }
+    }
+}
+
+fn is_zero_rated(line: &InvoiceLine) -> bool {
+    matches!(line.vat_category, VatCategory::Vat0 | VatCategory::Excluded)
+}
+
+// Reverse charge needs the right exemption article on every 0% line and a recipient able to
+// self-account the VAT. The non-zero rates on 1.2 / 2.2 are left to VAT-002 / VAT-009.
+pub struct ReverseChargeRules;
+
+impl ReverseChargeRules {
+    pub fn check(invoice: &Invoice, report: &mut ValidationReport) {
+        let Some(scenario) = ReverseChargeScenario::detect(invoice) else {
+            Self::check_missed_intra_eu(invoice, report);
+            return;
+        };
+
+        Self::check_exemptions(invoice, scenario, report);
+        Self::check_recipient(invoice, scenario, report);
+
+        // The report explains why the VAT is missing, the reader may not know the article
+        let (country, vat) = invoice.buyer().map(|b| (b.country, b.vat_number)).unwrap_or(("-", "-"));
+        report.add_info(
+            "RC-INFO",
+            Message::new(&format!("RC-INFO.{}", scenario.key()))
+                .with("country", country)
+                .with("vat", vat)
+                .with("exemption", scenario.exemption_category()),
+            None,
+            None,
+        );
+    }
+
+    fn check_exemptions(invoice: &Invoice, scenario: ReverseChargeScenario, report: &mut ValidationReport) {
+        let expected = scenario.exemption_category();
+
+        for (idx, line) in invoice.lines.iter().enumerate() {
+            let field = format!("line[{}].vatExemptionCategory", idx + 1);
+            match scenario {
+                ReverseChargeScenario::IntraEuGoods | ReverseChargeScenario::IntraEuServices => {
+                    if !is_zero_rated(line) || line.vat_exemption_category == Some(expected) {
+                        continue;
+                    }
+                    let found = line.vat_exemption_category.map(|c| c.to_string());
+                    report.add_finding(
+                        ExplainableError::new(
+                            "RC-001",
+                            Severity::Error,
+                            Message::new(&format!("RC-001.{}", scenario.key()))
+                                .with("line", idx + 1)
+                                .with("exemption", found.as_deref().unwrap_or("-")),
+                            Some(&field),
+                            found.as_deref(),
+                        )
+                        .with_allowed_values(&[expected.to_string()])
+                    );
+                    let action = if found.is_some() { FixAction::Replace } else { FixAction::Add };
+                    report.add_suggestion(
+                        FixHint::new("RC-001", &field, action, Message::new("hint.RC-001").with("exemption", expected))
+                            .with_proposed(expected)
+                    );
+                }
+                ReverseChargeScenario::Domestic => {
+                    // Art. 39a lines shift the whole VAT, the issuer charges none
+                    if line.vat_exemption_category != Some(expected) || is_zero_rated(line) {
+                        continue;
+                    }
+                    let field = format!("line[{}].vat_category", idx + 1);
+                    let category = line.vat_category.to_string();
+                    report.add_finding(
+                        ExplainableError::new(
+                            "RC-002",
+                            Severity::Error,
+                            Message::new("RC-002").with("line", idx + 1).with("category", line.vat_category),
+                            Some(&field),
+                            Some(&category),
+                        )
+                        .with_allowed_values(&[VatCategory::Vat0.to_string()])
+                    );
+                    report.add_suggestion(
+                        FixHint::new("RC-002", &field, FixAction::Replace, Message::new("hint.RC-002"))
+                            .with_proposed(VatCategory::Vat0)
+                    );
+                }
+            }
+        }
+    }
+
+    /// The recipient self-accounts the VAT, so it must be identified for VAT purposes.
+    /// A missing counterpart on 1.2 / 2.2 is CP-001's, its country is CP-002's.
+    fn check_recipient(invoice: &Invoice, scenario: ReverseChargeScenario, report: &mut ValidationReport) {
+        let buyer = invoice.buyer();
+
+        if scenario == ReverseChargeScenario::Domestic {
+            let domestic = buyer.as_ref().is_some_and(|b| {
+                CountryCatalog::get().vat_region(b.country, invoice.header.issue_date) == Some(VatRegion::Domestic)
+            });
+            if !domestic {
+                let field = buyer.as_ref().map(|b| format!("{}.country", b.field)).unwrap_or_else(|| "counterpart".to_string());
+                let country = buyer.as_ref().map(|b| b.country);
+                report.add_error("RC-003", Message::new("RC-003.domestic").with("country", country.unwrap_or("-")), Some(&field), country);
+                report.add_suggestion(FixHint::new("RC-003", &field, FixAction::Review, Message::new("hint.RC-003.domestic")));
+                return;
+            }
+        }
+
+        if let Some(buyer) = buyer.filter(|b| b.vat_number.trim().is_empty()) {
+            let field = format!("{}.vatNumber", buyer.field);
+            report.add_error("RC-003", Message::new("RC-003").with("country", buyer.country), Some(&field), None);
+            report.add_suggestion(FixHint::new("RC-003", &field, FixAction::Add, Message::new("hint.RC-003")));
+        }
+    }
+
+    /// A domestic sale to an EU business with nothing but 0% lines is most likely an intra-EU one
+    fn check_missed_intra_eu(invoice: &Invoice, report: &mut ValidationReport) {
+        let intra = match invoice.header.invoice_type {
+            InvoiceType::SalesInvoice => InvoiceType::SalesInvoiceIntra,
+            InvoiceType::ServiceInvoice => InvoiceType::ServiceInvoiceIntra,
+            _ => return,
+        };
+        let Some(buyer) = invoice.buyer() else { return };
+        let region = CountryCatalog::get().vat_region_for(buyer.country, invoice.header.issue_date, invoice.header.invoice_type.supply());
+        if region != Some(VatRegion::EuMember) || invoice.lines.is_empty() || !invoice.lines.iter().all(is_zero_rated) {
+            return;
+        }
+
+        let field = "invoiceHeader.invoiceType";
+        let found = invoice.header.invoice_type.to_string();
+        report.add_warning(
+            "RC-004",
+            Message::new("RC-004")
+                .with("invoice_type", invoice.header.invoice_type)
+                .with("country", buyer.country)
+                .with("expected", intra),
+            Some(field),
+            Some(&found),
+        );
+        report.add_suggestion(
+            FixHint::new("RC-004", field, FixAction::Replace, Message::new("hint.RC-004").with("expected", intra))
+                .with_proposed(intra)
+        );
+    }
+}
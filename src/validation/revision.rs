@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use crate::domain::invoice::Invoice;
+use super::diff::{self, InvoiceDiff};
+use super::result::{Severity, ValidationReport};
+
+/// The last logged validation of the same issuer / series / aa
+#[derive(Debug, Clone)]
+pub struct PreviousAttempt {
+    pub invoice: Invoice,
+    pub report: ValidationReport,
+    pub validated_at: DateTime<Utc>,
+}
+
+/// A finding identified across attempts by its code and field, the wording may change between them
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct FindingRef {
+    pub code: String,
+    pub field: Option<String>,
+    pub severity: Severity,
+}
+
+/// What changed since the previous attempt, in the invoice and in its findings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevisionComparison {
+    pub previous_validated_at: DateTime<Utc>,
+    pub diff: InvoiceDiff,
+    pub fixed: Vec<FindingRef>,      // Reported last time, gone now
+    pub introduced: Vec<FindingRef>, // Not reported last time
+}
+
+impl RevisionComparison {
+    pub fn between(previous: &PreviousAttempt, invoice: &Invoice, report: &ValidationReport) -> Self {
+        let before = finding_refs(&previous.report);
+        let after = finding_refs(report);
+
+        Self {
+            previous_validated_at: previous.validated_at,
+            diff: diff::compare(&previous.invoice, invoice),
+            fixed: before.difference(&after).cloned().collect(),
+            introduced: after.difference(&before).cloned().collect(),
+        }
+    }
+}
+
+// Info notes describe the invoice, they are not something to fix
+fn finding_refs(report: &ValidationReport) -> BTreeSet<FindingRef> {
+    report.errors.iter()
+        .filter(|e| e.severity != Severity::Info)
+        .map(|e| FindingRef { code: e.code.clone(), field: e.field.clone(), severity: e.severity })
+        .collect()
+}
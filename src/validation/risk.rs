@@ -0,0 +1,199 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use crate::domain::enums::{InvoiceType, VatCategory};
+use crate::domain::invoice::Invoice;
+use crate::i18n::catalog::Message;
+use crate::i18n::lang::Lang;
+use super::result::{Severity, ValidationReport};
+use super::rules_engine::RuleDefinition;
+
+// Statistical signal thresholds
+const ROUND_AMOUNT_MIN: Decimal = dec!(500);
+const ROUND_AMOUNT_STEP: Decimal = dec!(100);
+const ZERO_VAT_SHARE_MAX: Decimal = dec!(0.5);
+const UNUSUAL_VAT_SHARE_MAX: Decimal = dec!(0.05);
+const HISTORY_MIN_INVOICES: i64 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum RiskCategory {
+    Arithmetic,     // Totals, VAT amounts, sums
+    Classification, // Income classifications (E3)
+    Identity,       // VAT numbers, parties
+    Compliance,     // Document type, dates, currency, VAT regime
+    Statistical,    // Heuristic signals, not rule violations
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskFactor {
+    pub code: String,
+    pub category: RiskCategory,
+    pub points: u32,
+    pub reason: String,
+    #[serde(skip)]
+    pub message: Message,
+}
+
+impl RiskFactor {
+    fn new(code: &str, category: RiskCategory, points: u32, message: Message) -> Self {
+        Self {
+            code: code.to_string(),
+            category,
+            points,
+            reason: message.render(Lang::default()),
+            message,
+        }
+    }
+}
+
+/// What the issuer has submitted before, as far as the validation log knows
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IssuerHistory {
+    pub invoice_count: i64,
+    pub vat_category_counts: HashMap<String, i64>, // "1".."8" -> number of invoices using it
+    pub known_counterparts: HashSet<String>,
+}
+
+// Weights for the hardcoded checks in BusinessRules (YAML rules declare their own)
+fn builtin_weight(code: &str) -> Option<(RiskCategory, u32)> {
+    match code {
+        "BR-001" | "BR-002" => Some((RiskCategory::Arithmetic, 60)),
+        "BR-VAT-CALC" => Some((RiskCategory::Arithmetic, 40)),
+        "BR-CLS-TOTAL" => Some((RiskCategory::Classification, 40)),
+        "BR-010" | "BR-011" | "BR-CLS-PAIR" => Some((RiskCategory::Classification, 30)),
+        "BR-003" => Some((RiskCategory::Identity, 80)),
+        "BR-004" | "BR-VAT-EU" => Some((RiskCategory::Identity, 60)),
+        "BR-COUNTRY" => Some((RiskCategory::Identity, 40)),
+        "BR-005" => Some((RiskCategory::Compliance, 50)),
+        "BR-014" => Some((RiskCategory::Compliance, 40)),
+        "ISL-001" => Some((RiskCategory::Compliance, 50)),
+        "RC-001" | "RC-002" => Some((RiskCategory::Compliance, 50)),
+        "RC-003" => Some((RiskCategory::Identity, 50)),
+        "RC-004" => Some((RiskCategory::Compliance, 20)),
+        "SYS-001" => Some((RiskCategory::Compliance, 100)),
+        "BR-018" => Some((RiskCategory::Compliance, 50)),
+        "CORR-001" => Some((RiskCategory::Identity, 40)),
+        "CORR-002" => Some((RiskCategory::Arithmetic, 50)),
+        "HIST-001" => Some((RiskCategory::Identity, 30)),
+        _ => None,
+    }
+}
+
+fn default_weight(severity: Severity) -> u32 {
+    match severity {
+        Severity::Error => 40,
+        Severity::Warning => 10,
+        Severity::Info => 0,
+    }
+}
+
+pub struct RiskModel;
+
+impl RiskModel {
+    /// Scores the report findings and statistical signals, writing the result into the report.
+    /// Each rule code contributes its weight once, however many lines it fired on.
+    pub fn assess(
+        invoice: &Invoice,
+        report: &mut ValidationReport,
+        rules: &[RuleDefinition],
+        history: Option<&IssuerHistory>,
+    ) {
+        let mut factors: Vec<RiskFactor> = Vec::new();
+        let mut seen = HashSet::new();
+
+        for finding in &report.errors {
+            if !seen.insert(finding.code.clone()) {
+                continue;
+            }
+
+            let (category, points) = rules.iter()
+                .find(|r| r.id == finding.code)
+                .map(|r| (r.risk_category, r.risk_weight.unwrap_or_else(|| default_weight(r.severity))))
+                .or_else(|| builtin_weight(&finding.code))
+                .unwrap_or((RiskCategory::Compliance, default_weight(finding.severity)));
+
+            if points > 0 {
+                factors.push(RiskFactor {
+                    code: finding.code.clone(),
+                    category,
+                    points,
+                    reason: finding.reason.clone(),
+                    message: finding.message.clone(),
+                });
+            }
+        }
+
+        factors.extend(Self::statistical_signals(invoice, history));
+
+        let total: u32 = factors.iter().map(|f| f.points).sum();
+        report.risk_score = total.min(100) as u8;
+        report.risk_factors = factors;
+    }
+
+    fn statistical_signals(invoice: &Invoice, history: Option<&IssuerHistory>) -> Vec<RiskFactor> {
+        let mut signals = Vec::new();
+        let net = invoice.totals.total_net_amount;
+
+        if net >= ROUND_AMOUNT_MIN && (net % ROUND_AMOUNT_STEP).is_zero() {
+            signals.push(RiskFactor::new(
+                "RISK-ROUND", RiskCategory::Statistical, 10,
+                Message::new("RISK-ROUND").with("net", net),
+            ));
+        }
+
+        let zero_rated_expected = matches!(
+            invoice.header.invoice_type,
+            InvoiceType::SalesInvoiceIntra
+                | InvoiceType::SalesInvoiceThirdCountry
+                | InvoiceType::ServiceInvoiceIntra
+                | InvoiceType::ServiceInvoiceThirdCountry
+                | InvoiceType::ExpenseIntraAcquisition
+                | InvoiceType::ExpenseThirdCountryAcquisition
+                | InvoiceType::ExpenseIntraServices
+                | InvoiceType::ExpenseThirdCountryServices
+        );
+        let line_net: Decimal = invoice.lines.iter().map(|l| l.net_value.abs()).sum();
+        let zero_net: Decimal = invoice.lines.iter()
+            .filter(|l| matches!(l.vat_category, VatCategory::Vat0 | VatCategory::Excluded))
+            .map(|l| l.net_value.abs())
+            .sum();
+        if !zero_rated_expected && !line_net.is_zero() && zero_net / line_net > ZERO_VAT_SHARE_MAX {
+            signals.push(RiskFactor::new(
+                "RISK-ZERO-VAT", RiskCategory::Statistical, 15,
+                Message::new("RISK-ZERO-VAT").with("zero", zero_net).with("total", line_net),
+            ));
+        }
+
+        let Some(history) = history.filter(|h| h.invoice_count >= HISTORY_MIN_INVOICES) else {
+            return signals;
+        };
+
+        let mut unusual: Vec<String> = invoice.lines.iter()
+            .map(|l| l.vat_category.to_string())
+            .filter(|cat| {
+                let used = history.vat_category_counts.get(cat).copied().unwrap_or(0);
+                Decimal::from(used) / Decimal::from(history.invoice_count) < UNUSUAL_VAT_SHARE_MAX
+            })
+            .collect();
+        unusual.sort();
+        unusual.dedup();
+        if !unusual.is_empty() {
+            signals.push(RiskFactor::new(
+                "RISK-VAT-UNUSUAL", RiskCategory::Statistical, 15,
+                Message::new("RISK-VAT-UNUSUAL").with("categories", unusual.join(", ")),
+            ));
+        }
+
+        if let Some(cp) = &invoice.counterpart {
+            if !cp.vat_number.is_empty() && !history.known_counterparts.contains(&cp.vat_number) {
+                signals.push(RiskFactor::new(
+                    "RISK-NEW-CP", RiskCategory::Statistical, 10,
+                    Message::new("RISK-NEW-CP").with("vat", &cp.vat_number),
+                ));
+            }
+        }
+
+        signals
+    }
+}
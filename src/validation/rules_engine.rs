@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
-use crate::domain::invoice::Invoice;
-use crate::validation::result::{ValidationReport, Severity};
+use std::path::Path;
+use rust_decimal::Decimal;
+use crate::domain::invoice::{Invoice, InvoiceLine};
+use crate::domain::enums::VatCategory;
+use crate::domain::country::{CountryCatalog, VatRegion};
+use crate::i18n::catalog::Message;
+use crate::validation::result::{ExplainableError, FixAction, FixHint, ValidationReport, Severity};
+use crate::validation::risk::RiskCategory;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -27,7 +33,27 @@ pub enum RuleLogic {
         invoice_types: Vec<String>, // e.g., ["1.1", "1.2"]
     },
 
+    /// Check that no counterpart is sent for the invoice type (B2C documents)
+    /// Example: retail receipts 11.1/11.2 are issued to anonymous buyers
+    CounterpartForbidden {
+        invoice_types: Vec<String>,
+    },
+
+    /// Check that the document declares how it was paid (paymentMethods)
+    PaymentMethodsRequired {
+        invoice_types: Vec<String>,
+    },
+
+    /// Check that the amount paid with the listed payment method types stays within a ceiling
+    /// Example: cash ("3") above 500.00 on a retail receipt
+    PaymentAmountLimit {
+        invoice_types: Vec<String>,
+        payment_types: Vec<String>,
+        max_amount: Decimal,
+    },
+
     /// Check if classification is required based on invoice type
+    /// Counts the issuer's side: expense classifications on expense records (13.x, 14.x), income otherwise
     ClassificationRequired {
         invoice_types: Vec<String>,
         min_classifications: usize, // Minimum number required
@@ -46,8 +72,16 @@ pub enum RuleLogic {
         excluded_countries: Vec<String>,
     },
 
-    /// Check that all lines have negative values (for credit notes)
-    NegativeAmountsOnly {
+    /// Check the counterpart's VAT region on the issue date (country catalogue)
+    /// Example: 1.2/2.2 need another EU member state, 1.3/2.3 a third country
+    CounterpartRegion {
+        invoice_types: Vec<String>,
+        region: VatRegion,
+    },
+
+    /// Check that all lines carry the sign of the first non-zero line (for credit notes,
+    /// which myDATA accepts either all positive or all negative, never mixed)
+    ConsistentSign {
         invoice_types: Vec<String>,
     },
 
@@ -61,6 +95,20 @@ pub enum RuleLogic {
         invoice_types: Vec<String>,
         required_types: Vec<String>, // e.g., ["E3_561_001"]
     },
+
+    /// Flags lines whose field IS one of the listed values (inverse of LineValueAllowed)
+    /// Example: note every line using an island reduced rate (4, 5, 6)
+    LineValueMatches {
+        #[serde(default)]
+        invoice_types: Vec<String>, // Empty = all invoice types
+        field_path: String,
+        values: Vec<String>,
+    },
+
+    /// Fires once for every invoice of the listed types (used for informational notes)
+    InvoiceTypeMatch {
+        invoice_types: Vec<String>,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -68,8 +116,62 @@ pub struct RuleDefinition {
     pub id: String,
     pub description: String,
     pub severity: Severity,
+    #[serde(default = "default_risk_category")]
+    pub risk_category: RiskCategory,
+    /// Points added to the risk score when the rule fires (defaults by severity)
+    #[serde(default)]
+    pub risk_weight: Option<u32>,
     pub logic: RuleLogic,
-    pub error_message: String,
+    /// Fallback template for rules without an entry in the message catalogues
+    #[serde(default)]
+    pub error_message: Option<String>,
+    /// Example invoices proving the rule fires (or stays silent) as intended
+    #[serde(default, skip_serializing_if = "RuleExamples::is_empty")]
+    pub examples: RuleExamples,
+}
+
+fn default_risk_category() -> RiskCategory {
+    RiskCategory::Compliance
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuleExamples {
+    /// Invoices on which the rule must NOT report anything
+    #[serde(default)]
+    pub pass: Vec<RuleExample>,
+    /// Invoices on which the rule must report its code
+    #[serde(default)]
+    pub fail: Vec<RuleExample>,
+}
+
+impl RuleExamples {
+    pub fn is_empty(&self) -> bool {
+        self.pass.is_empty() && self.fail.is_empty()
+    }
+}
+
+/// An example invoice document, either inline XML or a path relative to the crate root
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RuleExample {
+    File { file: String },
+    Inline { xml: String },
+}
+
+impl RuleExample {
+    pub fn xml_content(&self, base_dir: &Path) -> std::io::Result<String> {
+        match self {
+            RuleExample::File { file } => std::fs::read_to_string(base_dir.join(file)),
+            RuleExample::Inline { xml } => Ok(xml.clone()),
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            RuleExample::File { file } => file,
+            RuleExample::Inline { .. } => "<inline>",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -78,6 +180,12 @@ pub struct RuleSet {
     pub rules: Vec<RuleDefinition>,
 }
 
+/// What the rules may rely on besides the invoice itself
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RuleContext {
+    pub island_registry: bool, // A registry is configured, ISL-001 decides who may use the island rates
+}
+
 #[derive(Default)]
 pub struct RulesEngine {
     rules: Vec<RuleDefinition>,
@@ -93,122 +201,282 @@ impl RulesEngine {
         Ok(Self { rules: rule_set.rules })
     }
 
+    pub fn rules(&self) -> &[RuleDefinition] {
+        &self.rules
+    }
+
     pub fn apply(&self, invoice: &Invoice, report: &mut ValidationReport) {
+        self.apply_in_context(invoice, &RuleContext::default(), report);
+    }
+
+    pub fn apply_in_context(&self, invoice: &Invoice, context: &RuleContext, report: &mut ValidationReport) {
         for rule in &self.rules {
-            match &rule.logic {
-                RuleLogic::LineValueAllowed { field_path, allowed_values } => {
+            Self::apply_rule_in_context(rule, invoice, context, report);
+        }
+    }
+
+    /// Evaluates a single rule against the invoice, adding any findings (and fix hints) to the report
+    pub fn apply_rule(rule: &RuleDefinition, invoice: &Invoice, report: &mut ValidationReport) {
+        Self::apply_rule_in_context(rule, invoice, &RuleContext::default(), report);
+    }
+
+    pub fn apply_rule_in_context(rule: &RuleDefinition, invoice: &Invoice, context: &RuleContext, report: &mut ValidationReport) {
+        match &rule.logic {
+            RuleLogic::LineValueAllowed { field_path, allowed_values } => {
+                for (idx, line) in invoice.lines.iter().enumerate() {
+                    let val = match field_path.as_str() {
+                        "vat_category" => Some(line.vat_category.to_string()),
+                        _ => None
+                    };
+
+                    if let Some(v) = val {
+                        if !allowed_values.contains(&v) && !Self::left_to_island_registry(context, field_path, line, allowed_values) {
+                            let field = format!("line[{}].{}", idx+1, field_path);
+                            Self::add_rule_error(report, rule, Self::rule_message(rule, invoice).with("line", idx + 1), Some(&field), Some(&v), Some(allowed_values));
+                            Self::add_rule_hint(report, rule, Self::replace_with_allowed(rule, &field, allowed_values));
+                        }
+                    }
+                }
+            },
+
+            RuleLogic::HeaderDependencyLine { header_field, header_value, line_check_field, allowed_values } => {
+                let header_match = match header_field.as_str() {
+                    "invoice_type" => invoice.header.invoice_type.to_string() == *header_value,
+                    _ => false
+                };
+
+                if header_match {
                     for (idx, line) in invoice.lines.iter().enumerate() {
-                        let val = match field_path.as_str() {
+                        let line_val = match line_check_field.as_str() {
                             "vat_category" => Some(line.vat_category.to_string()),
                             _ => None
                         };
 
-                        if let Some(v) = val {
-                            if !allowed_values.contains(&v) {
-                                Self::add_rule_error(report, rule, &rule.error_message.replace("{line}", &(idx + 1).to_string()), Some(&format!("line[{}].{}", idx+1, field_path)), Some(&v));
+                        if let Some(v) = line_val {
+                            if !allowed_values.contains(&v) && !Self::left_to_island_registry(context, line_check_field, line, allowed_values) {
+                                let field = format!("line[{}].{}", idx+1, line_check_field);
+                                Self::add_rule_error(report, rule, Self::rule_message(rule, invoice).with("line", idx + 1), Some(&field), Some(&v), Some(allowed_values));
+                                Self::add_rule_hint(report, rule, Self::replace_with_allowed(rule, &field, allowed_values));
                             }
                         }
                     }
-                },
-
-                RuleLogic::HeaderDependencyLine { header_field, header_value, line_check_field, allowed_values } => {
-                    let header_match = match header_field.as_str() {
-                        "invoice_type" => invoice.header.invoice_type.to_string() == *header_value,
-                        _ => false
-                    };
+                }
+            },
 
-                    if header_match {
-                        for (idx, line) in invoice.lines.iter().enumerate() {
-                            let line_val = match line_check_field.as_str() {
-                                "vat_category" => Some(line.vat_category.to_string()),
-                                _ => None
-                            };
+            RuleLogic::CounterpartRequired { invoice_types } => {
+                let inv_type = invoice.header.invoice_type.to_string();
+                if invoice_types.contains(&inv_type) && invoice.counterpart.is_none() {
+                    Self::add_rule_error(report, rule, Self::rule_message(rule, invoice), Some("counterpart"), None, None);
+                    Self::add_rule_hint(report, rule, FixHint::new(&rule.id, "counterpart", FixAction::Add, Message::new("hint.add_counterpart")));
+                }
+            },
 
-                            if let Some(v) = line_val {
-                                if !allowed_values.contains(&v) {
-                                    Self::add_rule_error(report, rule, &rule.error_message.replace("{line}", &(idx + 1).to_string()), Some(&format!("line[{}].{}", idx+1, line_check_field)), Some(&v));
-                                }
-                            }
-                        }
+            RuleLogic::CounterpartForbidden { invoice_types } => {
+                let inv_type = invoice.header.invoice_type.to_string();
+                if invoice_types.contains(&inv_type) {
+                    if let Some(cp) = &invoice.counterpart {
+                        Self::add_rule_error(report, rule, Self::rule_message(rule, invoice), Some("counterpart"), Some(&cp.vat_number), None);
+                        Self::add_rule_hint(report, rule, FixHint::new(&rule.id, "counterpart", FixAction::Review, Message::new("hint.remove_counterpart")));
                     }
-                },
+                }
+            },
+
+            RuleLogic::PaymentMethodsRequired { invoice_types } => {
+                let inv_type = invoice.header.invoice_type.to_string();
+                if invoice_types.contains(&inv_type) && invoice.payment_methods.is_empty() {
+                    Self::add_rule_error(report, rule, Self::rule_message(rule, invoice), Some("paymentMethods"), None, None);
+                    Self::add_rule_hint(report, rule, FixHint::new(&rule.id, "paymentMethods", FixAction::Add, Message::new("hint.add_payment_methods"))
+                        .with_expected(invoice.totals.total_gross_amount));
+                }
+            },
+
+            RuleLogic::PaymentAmountLimit { invoice_types, payment_types, max_amount } => {
+                let inv_type = invoice.header.invoice_type.to_string();
+                if invoice_types.contains(&inv_type) {
+                    let paid: Decimal = invoice.payment_methods.iter()
+                        .filter(|p| payment_types.contains(&p.payment_type.to_string()))
+                        .map(|p| p.amount)
+                        .sum();
 
-                RuleLogic::CounterpartRequired { invoice_types } => {
-                    let inv_type = invoice.header.invoice_type.to_string();
-                    if invoice_types.contains(&inv_type) && invoice.counterpart.is_none() {
-                        Self::add_rule_error(report, rule, &rule.error_message, Some("counterpart"), None);
+                    if paid > *max_amount {
+                        Self::add_rule_error(report, rule, Self::rule_message(rule, invoice).with("amount", paid).with("limit", max_amount), Some("paymentMethods"), Some(&paid.to_string()), None);
+                        Self::add_rule_hint(report, rule, FixHint::new(&rule.id, "paymentMethods", FixAction::Review, Message::new("hint.payment_limit").with("limit", max_amount))
+                            .with_expected(*max_amount));
                     }
-                },
+                }
+            },
 
-                RuleLogic::ClassificationRequired { invoice_types, min_classifications } => {
-                    let inv_type = invoice.header.invoice_type.to_string();
-                    if invoice_types.contains(&inv_type)
-                        && invoice.income_classifications.len() < *min_classifications {
-                            Self::add_rule_error(report, rule, &rule.error_message.replace("{count}", &invoice.income_classifications.len().to_string()), Some("incomeClassification"), None);
+            RuleLogic::ClassificationRequired { invoice_types, min_classifications } => {
+                let inv_type = invoice.header.invoice_type.to_string();
+                let (count, field, hint_key) = if invoice.header.invoice_type.is_expense_record() {
+                    (invoice.expense_classifications.len(), "expensesClassification", "hint.add_expense_classification")
+                } else {
+                    (invoice.income_classifications.len(), "incomeClassification", "hint.add_classification")
+                };
+                if invoice_types.contains(&inv_type) && count < *min_classifications {
+                    Self::add_rule_error(report, rule, Self::rule_message(rule, invoice).with("count", count), Some(field), None, None);
+                    Self::add_rule_hint(report, rule, FixHint::new(&rule.id, field, FixAction::Add, Message::new(hint_key))
+                        .with_expected(invoice.totals.total_net_amount));
+                }
+            },
+
+            RuleLogic::CurrencyExchangeRate { default_currency } => {
+                if invoice.header.currency != *default_currency && invoice.header.exchange_rate.is_none() {
+                    Self::add_rule_error(report, rule, Self::rule_message(rule, invoice), Some("exchangeRate"), Some(&invoice.header.currency), None);
+                    Self::add_rule_hint(report, rule, FixHint::new(&rule.id, "exchangeRate", FixAction::Add, Message::new("hint.add_exchange_rate").with("default_currency", default_currency).with("currency", &invoice.header.currency)));
+                }
+            },
+
+            RuleLogic::CounterpartCountry { invoice_types, excluded_countries } => {
+                let inv_type = invoice.header.invoice_type.to_string();
+                if invoice_types.contains(&inv_type) {
+                    if let Some(cp) = &invoice.counterpart {
+                        if excluded_countries.contains(&cp.country) {
+                            Self::add_rule_error(report, rule, Self::rule_message(rule, invoice), Some("counterpart.country"), Some(&cp.country), None);
+                            Self::add_rule_hint(report, rule, FixHint::new(&rule.id, "counterpart.country", FixAction::Review, Message::new("hint.excluded_country").with("countries", excluded_countries.join(", "))));
                         }
-                },
+                    }
+                }
+            },
 
-                RuleLogic::CurrencyExchangeRate { default_currency } => {
-                    if invoice.header.currency != *default_currency && invoice.header.exchange_rate.is_none() {
-                        Self::add_rule_error(report, rule, &rule.error_message, Some("exchangeRate"), Some(&invoice.header.currency));
+            RuleLogic::CounterpartRegion { invoice_types, region } => {
+                let inv_type = invoice.header.invoice_type.to_string();
+                if invoice_types.contains(&inv_type) {
+                    if let Some(cp) = &invoice.counterpart {
+                        // Unknown codes are reported by the built-in country check
+                        let found = CountryCatalog::get().vat_region_for(&cp.country, invoice.header.issue_date, invoice.header.invoice_type.supply());
+                        if found.is_some_and(|found| found != *region) {
+                            Self::add_rule_error(report, rule, Self::rule_message(rule, invoice).with("country", &cp.country), Some("counterpart.country"), Some(&cp.country), None);
+                            Self::add_rule_hint(report, rule, FixHint::new(&rule.id, "counterpart.country", FixAction::Review, Message::new(&format!("hint.region.{}", region))));
+                        }
                     }
-                },
-
-                RuleLogic::CounterpartCountry { invoice_types, excluded_countries } => {
-                    let inv_type = invoice.header.invoice_type.to_string();
-                    if invoice_types.contains(&inv_type) {
-                        if let Some(cp) = &invoice.counterpart {
-                            if excluded_countries.contains(&cp.country) {
-                                Self::add_rule_error(report, rule, &rule.error_message, Some("counterpart.country"), Some(&cp.country));
-                            }
+                }
+            },
+
+            RuleLogic::ConsistentSign { invoice_types } => {
+                let inv_type = invoice.header.invoice_type.to_string();
+                if invoice_types.contains(&inv_type) {
+                    let mut signed = invoice.lines.iter().filter(|l| !l.net_value.is_zero());
+                    let negative = signed.next().map(|l| l.net_value.is_sign_negative());
+                    for (idx, line) in invoice.lines.iter().enumerate() {
+                        if line.net_value.is_zero() || Some(line.net_value.is_sign_negative()) == negative {
+                            continue;
                         }
+                        let field = format!("line[{}].netValue", idx+1);
+                        Self::add_rule_error(report, rule, Self::rule_message(rule, invoice).with("line", idx + 1), Some(&field), Some(&line.net_value.to_string()), None);
+                        Self::add_rule_hint(report, rule, FixHint::new(&rule.id, &field, FixAction::Replace, Message::new("hint.negate_amount"))
+                            .with_proposed(-line.net_value));
                     }
-                },
-
-                RuleLogic::NegativeAmountsOnly { invoice_types } => {
-                    let inv_type = invoice.header.invoice_type.to_string();
-                    if invoice_types.contains(&inv_type) {
-                        for (idx, line) in invoice.lines.iter().enumerate() {
-                            if line.net_value.is_sign_positive() {
-                                Self::add_rule_error(report, rule, &rule.error_message.replace("{line}", &(idx + 1).to_string()), Some(&format!("line[{}].netValue", idx+1)), Some(&line.net_value.to_string()));
-                            }
+                }
+            },
+
+            RuleLogic::NoNegativeAmounts { invoice_types } => {
+                let inv_type = invoice.header.invoice_type.to_string();
+                if invoice_types.contains(&inv_type) {
+                    for (idx, line) in invoice.lines.iter().enumerate() {
+                        if line.net_value.is_sign_negative() {
+                            let field = format!("line[{}].netValue", idx+1);
+                            Self::add_rule_error(report, rule, Self::rule_message(rule, invoice).with("line", idx + 1), Some(&field), Some(&line.net_value.to_string()), None);
+                            Self::add_rule_hint(report, rule, FixHint::new(&rule.id, &field, FixAction::Replace, Message::new("hint.positive_amount"))
+                                .with_proposed(line.net_value.abs()));
                         }
                     }
-                },
-
-                RuleLogic::NoNegativeAmounts { invoice_types } => {
-                    let inv_type = invoice.header.invoice_type.to_string();
-                    if invoice_types.contains(&inv_type) {
-                        for (idx, line) in invoice.lines.iter().enumerate() {
-                            if line.net_value.is_sign_negative() {
-                                Self::add_rule_error(report, rule, &rule.error_message.replace("{line}", &(idx + 1).to_string()), Some(&format!("line[{}].netValue", idx+1)), Some(&line.net_value.to_string()));
-                            }
+                }
+            },
+
+            RuleLogic::ClassificationTypeRequired { invoice_types, required_types } => {
+                let inv_type = invoice.header.invoice_type.to_string();
+                if invoice_types.contains(&inv_type) {
+                    for req_type in required_types {
+                        let found = invoice.income_classifications.iter()
+                            .any(|c| c.classification_type.as_ref() == Some(req_type));
+
+                        if !found {
+                            Self::add_rule_error(report, rule, Self::rule_message(rule, invoice).with("type", req_type), Some("incomeClassification"), Some(req_type), Some(required_types));
+                            // With a single classification the fix is to retype it, otherwise one has to be added
+                            let hint = if invoice.income_classifications.len() == 1 {
+                                FixHint::new(&rule.id, "incomeClassification[1].classificationType", FixAction::Replace, Message::new("hint.replace_classification_type").with("type", req_type))
+                            } else {
+                                FixHint::new(&rule.id, "incomeClassification", FixAction::Add, Message::new("hint.add_classification_type").with("type", req_type))
+                            };
+                            Self::add_rule_hint(report, rule, hint.with_allowed_values(required_types).with_proposed(req_type));
                         }
                     }
-                },
+                }
+            },
 
-                RuleLogic::ClassificationTypeRequired { invoice_types, required_types } => {
-                    let inv_type = invoice.header.invoice_type.to_string();
-                    if invoice_types.contains(&inv_type) {
-                        for req_type in required_types {
-                            let found = invoice.income_classifications.iter()
-                                .any(|c| c.classification_type.as_ref() == Some(req_type));
+            RuleLogic::LineValueMatches { invoice_types, field_path, values } => {
+                let inv_type = invoice.header.invoice_type.to_string();
+                if invoice_types.is_empty() || invoice_types.contains(&inv_type) {
+                    for (idx, line) in invoice.lines.iter().enumerate() {
+                        let val = match field_path.as_str() {
+                            "vat_category" => Some(line.vat_category.to_string()),
+                            _ => None
+                        };
 
-                            if !found {
-                                Self::add_rule_error(report, rule, &rule.error_message.replace("{type}", req_type), Some("incomeClassification"), Some(req_type));
+                        if let Some(v) = val {
+                            if values.contains(&v) {
+                                let field = format!("line[{}].{}", idx+1, field_path);
+                                let allowed: Vec<String> = VatCategory::ALL.iter()
+                                    .map(|c| c.to_string())
+                                    .filter(|c| !values.contains(c))
+                                    .collect();
+                                Self::add_rule_error(report, rule, Self::rule_message(rule, invoice).with("line", idx + 1), Some(&field), Some(&v), Some(&allowed));
+                                Self::add_rule_hint(report, rule, FixHint::new(&rule.id, &field, FixAction::Review, Message::new("hint.review_value")).with_allowed_values(&allowed));
                             }
                         }
                     }
-                },
-            }
+                }
+            },
+
+            RuleLogic::InvoiceTypeMatch { invoice_types } => {
+                let inv_type = invoice.header.invoice_type.to_string();
+                if invoice_types.contains(&inv_type) {
+                    Self::add_rule_error(report, rule, Self::rule_message(rule, invoice), Some("invoiceHeader.invoiceType"), Some(&inv_type), None);
+                    Self::add_rule_hint(report, rule, FixHint::new(&rule.id, "invoiceHeader.invoiceType", FixAction::Review, Message::new("hint.review_invoice_type")));
+                }
+            },
         }
     }
 
-    fn add_rule_error(report: &mut ValidationReport, rule: &RuleDefinition, message: &str, field: Option<&str>, value: Option<&str>) {
-        match rule.severity {
-            Severity::Error => report.add_error(&rule.id, message, field, value),
-            Severity::Warning => report.add_warning(&rule.id, message),
-            Severity::Info => {} // Could add info method if needed
+    // Proposes the first allowed value; YAML lists them in order of preference
+    // With a registry, an island rate standing in for an allowed mainland rate is ISL-001's to judge
+    fn left_to_island_registry(context: &RuleContext, field: &str, line: &InvoiceLine, allowed_values: &[String]) -> bool {
+        context.island_registry
+            && field == "vat_category"
+            && line.vat_category.mainland_equivalent().is_some_and(|mainland| allowed_values.contains(&mainland.to_string()))
+    }
+
+    fn replace_with_allowed(rule: &RuleDefinition, field: &str, allowed_values: &[String]) -> FixHint {
+        let hint = FixHint::new(&rule.id, field, FixAction::Replace, Message::new("hint.allowed_values").with("allowed", allowed_values.join(", ")))
+            .with_allowed_values(allowed_values);
+        // Picking one of several would be a guess, and the VAT amount would no longer fit the line
+        match allowed_values {
+            [only] => hint.with_proposed(only),
+            _ => hint,
+        }
+    }
+
+    // Rule messages live in the catalogues under the rule id; every rule gets the header context
+    fn rule_message(rule: &RuleDefinition, invoice: &Invoice) -> Message {
+        Message::new(&rule.id)
+            .with_fallback(rule.error_message.as_deref())
+            .with("invoice_type", invoice.header.invoice_type)
+            .with("currency", &invoice.header.currency)
+    }
+
+    fn add_rule_error(report: &mut ValidationReport, rule: &RuleDefinition, message: Message, field: Option<&str>, value: Option<&str>, allowed_values: Option<&[String]>) {
+        let finding = ExplainableError::new(&rule.id, rule.severity, message, field, value);
+        report.add_finding(match allowed_values {
+            Some(allowed) => finding.with_allowed_values(allowed),
+            None => finding,
+        });
+    }
+
+    // Info notes describe the document, there is nothing to fix
+    fn add_rule_hint(report: &mut ValidationReport, rule: &RuleDefinition, hint: FixHint) {
+        if rule.severity != Severity::Info {
+            report.add_suggestion(hint);
         }
     }
 }
@@ -1,7 +1,7 @@
-use crate::domain::invoice::{Invoice, InvoiceHeader, InvoiceLine, Issuer, Counterpart};
+use crate::domain::invoice::{Invoice, InvoiceHeader, InvoiceLine, Issuer, Counterpart, PaymentDetail};
 use crate::domain::totals::InvoiceTotals;
 use crate::domain::enums::{InvoiceType, VatCategory};
-use crate::domain::classification::IncomeClassification;
+use crate::domain::classification::{ExpenseClassification, IncomeClassification};
 use super::parser::XmlInvoice;
 use rust_decimal::Decimal;
 use rust_decimal::prelude::FromPrimitive;
@@ -13,6 +13,9 @@ pub struct Normalizer;
 
 impl Normalizer {
     pub fn normalize(xml_invoice: XmlInvoice) -> Result<Invoice, String> {
+        let uid = xml_invoice.uid.or(xml_invoice.invoice_header.uid);
+        let mark = xml_invoice.mark.map(|m| m.trim().to_string());
+
         // 1. Header
         let issue_date = NaiveDate::parse_from_str(&xml_invoice.invoice_header.issue_date, "%Y-%m-%d")
             .map_err(|e| format!("Invalid date format: {}", e))?;
@@ -22,6 +25,10 @@ impl Normalizer {
         let invoice_type = serde_json::from_value(serde_json::json!(inv_type_str))
             .unwrap_or(InvoiceType::Unknown); // Simplified for now
 
+        let exchange_rate = xml_invoice.invoice_header.exchange_rate
+            .map(|rate| Decimal::from_f64(rate).ok_or_else(|| format!("Invalid exchange rate {}", rate)))
+            .transpose()?;
+
         let header = InvoiceHeader {
             series: xml_invoice.invoice_header.series,
             aa: xml_invoice.invoice_header.aa,
@@ -29,7 +36,12 @@ impl Normalizer {
             issue_time: None, // Parse if exists
             invoice_type,
             currency: xml_invoice.invoice_header.currency.unwrap_or_else(|| "EUR".to_string()),
-            exchange_rate: None,
+            exchange_rate,
+            correlated_invoices: xml_invoice.invoice_header.correlated_invoices.iter()
+                .map(|m| m.trim().to_string())
+                .filter(|m| !m.is_empty())
+                .collect(),
+            self_pricing: xml_invoice.invoice_header.self_pricing.unwrap_or(false),
         };
 
         // 2. Issuer
@@ -71,6 +83,7 @@ impl Normalizer {
                  net_value: net,
                  vat_category: vat_cat,
                  vat_amount: vat_amt,
+                 vat_exemption_category: row.vat_exemption_category,
                  quantity: None,
                  measurement_unit: None,
              });
@@ -113,8 +126,41 @@ impl Normalizer {
             }
         }
 
+        let mut expense_classifications = Vec::new();
+        if let Some(xml_classifications) = sum.expenses_classification {
+            for item in xml_classifications {
+                let amount = Decimal::from_f64(item.amount)
+                    .ok_or_else(|| format!(
+                        "Invalid expense classification amount for type {:?} category {:?}",
+                        item.classification_type, item.classification_category
+                    ))?;
+
+                expense_classifications.push(ExpenseClassification {
+                    classification_type: item.classification_type,
+                    classification_category: item.classification_category,
+                    amount,
+                });
+            }
+        }
+
+        // 7. Payment methods
+        let mut payment_methods = Vec::new();
+        if let Some(xml_payments) = xml_invoice.payment_methods {
+            for detail in xml_payments.payment_method_details {
+                let amount = Decimal::from_f64(detail.amount)
+                    .ok_or_else(|| format!("Invalid payment amount for type {}", detail.r#type))?;
+
+                payment_methods.push(PaymentDetail {
+                    payment_type: detail.r#type,
+                    amount,
+                    info: detail.payment_method_info,
+                });
+            }
+        }
+
         Ok(Invoice {
-            uid: None,
+            uid,
+            mark,
             header,
             issuer,
             counterpart,
@@ -122,6 +168,8 @@ impl Normalizer {
             totals,
             vat_breakdown: vec![], // To be calculated or extracted if detailed
             income_classifications,
+            expense_classifications,
+            payment_methods,
         })
     }
 }
\ No newline at end of file
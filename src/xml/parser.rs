@@ -11,6 +11,9 @@ pub struct AadeBook {
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct XmlInvoice {
+    pub uid: Option<String>,
+    pub mark: Option<String>,
+
     // Header
     pub issuer: Option<XmlIssuer>,
     pub counterpart: Option<XmlCounterpart>,
@@ -54,6 +57,13 @@ pub struct XmlInvoiceHeader {
     #[serde(rename = "invoiceType")]
     pub invoice_type: String, // e.g., "1.1"
     pub currency: Option<String>,
+    #[serde(rename = "exchangeRate")]
+    pub exchange_rate: Option<f64>,
+    pub uid: Option<String>, // Some producers place uid in the header instead of the invoice
+    #[serde(rename = "correlatedInvoices", default)]
+    pub correlated_invoices: Vec<String>, // MARKs of the invoices this one corrects
+    #[serde(rename = "selfPricing")]
+    pub self_pricing: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -66,6 +76,8 @@ pub struct XmlInvoiceRow {
     pub vat_category: i32,
     #[serde(rename = "vatAmount")]
     pub vat_amount: f64,
+    #[serde(rename = "vatExemptionCategory")]
+    pub vat_exemption_category: Option<i32>, // Article the 0% / exempt line relies on
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -86,6 +98,8 @@ pub struct XmlInvoiceSummary {
     pub total_gross_value: f64,
     #[serde(rename = "incomeClassification")]
     pub income_classification: Option<Vec<XmlIncomeClassification>>,
+    #[serde(rename = "expensesClassification")]
+    pub expenses_classification: Option<Vec<XmlExpensesClassification>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -97,6 +111,15 @@ pub struct XmlIncomeClassification {
     pub amount: f64,
 }
 
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct XmlExpensesClassification {
+    #[serde(rename = "classificationType")]
+    pub classification_type: Option<String>,
+    #[serde(rename = "classificationCategory")]
+    pub classification_category: Option<String>,
+    pub amount: f64,
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct XmlPaymentMethods {
     #[serde(rename = "paymentMethodDetails")]
@@ -0,0 +1,34 @@
+use aade_validator::api::auth::{generate_key, hash_key, key_prefix, presented_key};
+use axum::http::{header, HeaderMap, HeaderValue};
+
+fn with(name: header::HeaderName, value: &'static str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(name, HeaderValue::from_static(value));
+    headers
+}
+
+#[test]
+fn test_generated_keys_are_random_and_stored_hashed() {
+    let key = generate_key();
+    assert!(key.starts_with("aade_"));
+    assert_eq!(key.len(), 5 + 64);
+    assert_ne!(key, generate_key());
+
+    let hash = hash_key(&key);
+    assert_eq!(hash.len(), 64);
+    assert_eq!(hash, hash_key(&key));
+    assert!(!hash.contains(&key[5..]));
+
+    // Enough to tell keys apart, far from enough to use one
+    assert_eq!(key_prefix(&key), &key[..12]);
+}
+
+#[test]
+fn test_key_is_read_from_bearer_or_api_key_header() {
+    assert_eq!(presented_key(&with(header::AUTHORIZATION, "Bearer aade_123")), Some("aade_123"));
+    assert_eq!(presented_key(&with(header::HeaderName::from_static("x-api-key"), " aade_456 ")), Some("aade_456"));
+
+    assert_eq!(presented_key(&with(header::AUTHORIZATION, "Basic dXNlcjpwYXNz")), None);
+    assert_eq!(presented_key(&with(header::AUTHORIZATION, "Bearer ")), None);
+    assert_eq!(presented_key(&HeaderMap::new()), None);
+}
@@ -0,0 +1,32 @@
+use aade_validator::api::validate::ValidateOptions;
+use aade_validator::i18n::lang::Lang;
+use aade_validator::jobs::JobStatus;
+
+#[test]
+fn test_job_status_round_trips_through_database_text() {
+    for status in [JobStatus::Queued, JobStatus::Running, JobStatus::Completed, JobStatus::Cancelled, JobStatus::Failed] {
+        assert_eq!(JobStatus::parse(status.as_str()), Some(status));
+        // The API reports the same text the table stores
+        assert_eq!(serde_json::to_value(status).unwrap(), status.as_str());
+    }
+    assert_eq!(JobStatus::parse("paused"), None);
+}
+
+#[test]
+fn test_only_queued_and_running_jobs_can_be_cancelled() {
+    assert!(!JobStatus::Queued.is_finished());
+    assert!(!JobStatus::Running.is_finished());
+    assert!(JobStatus::Completed.is_finished());
+    assert!(JobStatus::Cancelled.is_finished());
+    assert!(JobStatus::Failed.is_finished());
+}
+
+#[test]
+fn test_job_options_survive_storage() {
+    let options = ValidateOptions { lang: Lang::En, stateful: true, compare_previous: false };
+    let stored: ValidateOptions = serde_json::from_value(serde_json::to_value(options).unwrap()).unwrap();
+
+    assert_eq!(stored.lang, Lang::En);
+    assert!(stored.stateful);
+    assert!(!stored.compare_previous);
+}
@@ -0,0 +1,46 @@
+use aade_validator::api::batch_stream::{BatchEvent, StreamFormat};
+use aade_validator::api::batch_summary::BatchSummary;
+use aade_validator::api::validate::BatchFileResult;
+use axum::http::{header, HeaderMap, HeaderValue};
+use serde_json::json;
+
+fn accepting(value: &'static str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::ACCEPT, HeaderValue::from_static(value));
+    headers
+}
+
+#[test]
+fn test_stream_format_follows_accept_header() {
+    assert_eq!(StreamFormat::negotiate(&accepting("text/event-stream")), Some(StreamFormat::EventStream));
+    assert_eq!(StreamFormat::negotiate(&accepting("application/x-ndjson")), Some(StreamFormat::Ndjson));
+    assert_eq!(StreamFormat::negotiate(&accepting("application/json")), None);
+    assert_eq!(StreamFormat::negotiate(&HeaderMap::new()), None);
+}
+
+#[test]
+fn test_events_carry_their_name() {
+    let result: BatchFileResult = serde_json::from_value(json!({
+        "filename": "a.xml",
+        "status": "error",
+        "reports": [],
+        "book": null,
+        "error_message": "XML Parse Failed",
+    })).unwrap();
+    let summary = BatchSummary::from_results([&result]);
+
+    let events = [
+        BatchEvent::Result(Box::new(result)),
+        BatchEvent::Progress { processed: 1, total: 1 },
+        BatchEvent::Summary(Box::new(summary)),
+    ];
+    let json: Vec<_> = events.iter().map(|e| serde_json::to_value(e).unwrap()).collect();
+
+    for (event, value) in events.iter().zip(&json) {
+        assert_eq!(value["event"], event.name());
+    }
+    assert_eq!(json[0]["filename"], "a.xml");
+    assert_eq!(json[1], json!({ "event": "progress", "processed": 1, "total": 1 }));
+    assert_eq!(json[2]["files"], 1);
+    assert_eq!(json[2]["failed_files"], 1);
+}
@@ -0,0 +1,116 @@
+use aade_validator::api::batch_summary::{BatchSummary, InvoiceAmounts};
+use aade_validator::api::validate::{BatchFileResult, FileStatus};
+use aade_validator::domain::enums::{InvoiceType, VatCategory};
+use aade_validator::validation::business_rules::BusinessRules;
+use aade_validator::xml::normalizer::Normalizer;
+use aade_validator::xml::parser::AadeBook;
+use quick_xml::de::from_str;
+use rust_decimal_macros::dec;
+
+// What the batch endpoint reports for a sample file, without the validation log
+fn result(sample: &str) -> BatchFileResult {
+    let xml = std::fs::read_to_string(format!("tests/samples/{}", sample)).expect("Failed to read sample file");
+    let book: AadeBook = from_str(&xml).expect("Failed to parse XML");
+    let invoices: Vec<_> = book.invoices.into_iter()
+        .map(|i| Normalizer::normalize(i).expect("Normalization failed"))
+        .collect();
+
+    BatchFileResult {
+        filename: sample.to_string(),
+        status: FileStatus::Success,
+        reports: invoices.iter().map(BusinessRules::validate).collect(),
+        book: None,
+        error_message: None,
+        archive: None,
+        amounts: invoices.iter().map(InvoiceAmounts::from_invoice).collect(),
+    }
+}
+
+fn failed(filename: &str) -> BatchFileResult {
+    serde_json::from_value(serde_json::json!({
+        "filename": filename,
+        "status": "error",
+        "reports": [],
+        "book": null,
+        "error_message": "XML Parse Failed",
+    })).unwrap()
+}
+
+fn batch() -> Vec<BatchFileResult> {
+    vec![
+        result("v01_simple_invoice_1.1.xml"),
+        result("v02_multiple_lines_e3.xml"),
+        result("i16_BR-001_net_mismatch_with_counterpart.xml"),
+        result("i16_BR-001_net_mismatch_with_counterpart.xml"),
+        result("i17_BR-002_vat_mismatch_with_counterpart.xml"),
+        failed("broken.xml"),
+    ]
+}
+
+#[test]
+fn test_summary_counts_files_invoices_and_statuses() {
+    let summary = BatchSummary::from_results(&batch());
+
+    assert_eq!(summary.files, 6);
+    assert_eq!(summary.failed_files, 1);
+    assert_eq!(summary.invoices, 5);
+    assert_eq!((summary.green, summary.yellow, summary.red), (2, 0, 3));
+
+    let codes: Vec<_> = summary.top_error_codes.iter().map(|c| (c.code.as_str(), c.count)).collect();
+    assert_eq!(codes, [("BR-001", 2), ("BR-002", 1)]);
+}
+
+#[test]
+fn test_amounts_cover_valid_invoices_only() {
+    let summary = BatchSummary::from_results(&batch());
+
+    // v01 (100 + 24 at 24%) and v02 (150 + 36 at 24%, 50 + 6.5 at 13%); the Red invoices are left out
+    assert_eq!(summary.totals.net, dec!(300));
+    assert_eq!(summary.totals.vat, dec!(66.5));
+
+    let categories: Vec<_> = summary.by_vat_category.iter()
+        .map(|c| (c.vat_category, c.amounts.net, c.amounts.vat, c.amounts.gross))
+        .collect();
+    assert_eq!(categories, [
+        (VatCategory::Vat24, dec!(250), dec!(60), dec!(310)),
+        (VatCategory::Vat13, dec!(50), dec!(6.5), dec!(56.5)),
+    ]);
+
+    assert_eq!(summary.by_invoice_type.len(), 1);
+    assert_eq!(summary.by_invoice_type[0].invoice_type, InvoiceType::SalesInvoice);
+    assert_eq!(summary.by_invoice_type[0].invoices, 2);
+    assert_eq!(summary.by_invoice_type[0].amounts.net, dec!(300));
+}
+
+#[test]
+fn test_credit_notes_are_subtracted() {
+    // v02 (200 net) less a credit note for v01 (100 net), written positive and negative
+    let mut credit = result("v01_simple_invoice_1.1.xml");
+    credit.amounts[0].invoice_type = InvoiceType::CreditNote;
+    let mut negative = credit.clone();
+    let amounts = &mut negative.amounts[0];
+    (amounts.net, amounts.vat, amounts.gross) = (-amounts.net, -amounts.vat, -amounts.gross);
+
+    for credit in [credit, negative] {
+        let summary = BatchSummary::from_results(&[result("v02_multiple_lines_e3.xml"), credit]);
+
+        assert_eq!((summary.totals.net, summary.totals.vat), (dec!(100), dec!(18.5)));
+        let vat24 = summary.by_vat_category.iter().find(|c| c.vat_category == VatCategory::Vat24).unwrap();
+        assert_eq!(vat24.amounts.net, dec!(50));
+        let by_type: Vec<_> = summary.by_invoice_type.iter().map(|t| (t.invoice_type, t.amounts.net)).collect();
+        assert_eq!(by_type, [(InvoiceType::SalesInvoice, dec!(200)), (InvoiceType::CreditNote, dec!(-100))]);
+    }
+}
+
+#[test]
+fn test_summary_csv_export() {
+    let csv = BatchSummary::from_results(&batch()).to_csv();
+    let lines: Vec<_> = csv.lines().collect();
+
+    assert_eq!(lines[0], "section,key,count,net,vat,gross");
+    assert!(lines.contains(&"files,failed,1,,,"));
+    assert!(lines.contains(&"status,red,3,,,"));
+    assert!(lines.contains(&"error_code,BR-001,2,,,"));
+    assert!(lines.contains(&"vat_category,2,,50,6.5,56.5"));
+    assert!(lines.iter().any(|l| l.starts_with("invoice_type,1.1,2,300,66.5,")));
+}
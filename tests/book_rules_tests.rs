@@ -0,0 +1,144 @@
+use aade_validator::domain::invoice::Invoice;
+use aade_validator::validation::book_rules::BookRules;
+use aade_validator::validation::result::{Severity, ValidationStatus};
+use aade_validator::xml::normalizer::Normalizer;
+use aade_validator::xml::parser::AadeBook;
+use chrono::NaiveDate;
+use quick_xml::de::from_str;
+use rust_decimal_macros::dec;
+use std::fs;
+
+fn load_sample(name: &str) -> Invoice {
+    let xml_content = fs::read_to_string(format!("tests/samples/{}", name))
+        .expect("Failed to read sample file");
+    let book: AadeBook = from_str(&xml_content).expect("Failed to parse XML");
+    Normalizer::normalize(book.invoices.into_iter().next().unwrap()).expect("Normalization failed")
+}
+
+// Copy of the valid sample with its own number, date and amount
+fn numbered(aa: &str, date: &str, net: rust_decimal::Decimal) -> Invoice {
+    let mut invoice = load_sample("valid_invoice.xml");
+    invoice.header.aa = aa.to_string();
+    invoice.header.issue_date = NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+    invoice.lines[0].net_value = net;
+    invoice
+}
+
+fn codes(invoices: &[Invoice]) -> Vec<String> {
+    BookRules::validate(invoices).errors.into_iter().map(|e| e.code).collect()
+}
+
+#[test]
+fn test_clean_sequence_is_green() {
+    let invoices = vec![
+        numbered("1", "2023-10-01", dec!(100)),
+        numbered("2", "2023-10-02", dec!(200)),
+        numbered("3", "2023-10-02", dec!(300)),
+    ];
+    let report = BookRules::validate(&invoices);
+
+    assert_eq!(report.status, ValidationStatus::Green, "Unexpected findings: {:?}", report.errors);
+}
+
+#[test]
+fn test_duplicate_identity_proposes_next_number() {
+    let invoices = vec![
+        numbered("1", "2023-10-01", dec!(100)),
+        numbered("2", "2023-10-02", dec!(200)),
+        numbered("2", "2023-10-03", dec!(300)),
+    ];
+    let report = BookRules::validate(&invoices);
+
+    assert_eq!(report.status, ValidationStatus::Red);
+    let finding = report.findings(Severity::Error).find(|e| e.code == "BOOK-001").expect("Missing BOOK-001");
+    assert_eq!(finding.field.as_deref(), Some("invoice[3].invoiceHeader.aa"));
+
+    let hint = report.suggestions.iter().find(|h| h.code == "BOOK-001").expect("Missing BOOK-001 hint");
+    assert_eq!(hint.proposed_value.as_deref(), Some("3"));
+}
+
+#[test]
+fn test_duplicate_uid() {
+    let mut first = numbered("1", "2023-10-01", dec!(100));
+    let mut second = numbered("2", "2023-10-02", dec!(200));
+    first.uid = Some("ABC123".to_string());
+    second.uid = Some("ABC123".to_string());
+
+    assert_eq!(codes(&[first, second]), vec!["BOOK-002"]);
+}
+
+#[test]
+fn test_gap_and_out_of_order_dates() {
+    let invoices = vec![
+        numbered("1", "2023-10-05", dec!(100)),
+        numbered("4", "2023-10-01", dec!(200)),
+    ];
+    let report = BookRules::validate(&invoices);
+
+    assert_eq!(report.status, ValidationStatus::Yellow);
+    let gap = report.errors.iter().find(|e| e.code == "BOOK-003").expect("Missing BOOK-003");
+    assert!(gap.reason.contains('2') && gap.reason.contains('3'), "Gap should name 2 to 3: {}", gap.reason);
+    let order = report.errors.iter().find(|e| e.code == "BOOK-004").expect("Missing BOOK-004");
+    assert_eq!(order.field.as_deref(), Some("invoice[2].invoiceHeader.issueDate"));
+}
+
+#[test]
+fn test_resubmitted_number_is_only_a_duplicate() {
+    // Number 2 sent again with an earlier date is not misdated against itself
+    let invoices = vec![
+        numbered("1", "2023-10-01", dec!(100)),
+        numbered("2", "2023-10-03", dec!(200)),
+        numbered("2", "2023-10-02", dec!(300)),
+        numbered("3", "2023-10-04", dec!(400)),
+    ];
+
+    assert_eq!(codes(&invoices), vec!["BOOK-001"]);
+}
+
+#[test]
+fn test_series_are_checked_separately() {
+    let mut other_series = numbered("7", "2023-09-01", dec!(300));
+    other_series.header.series = "B".to_string();
+    let invoices = vec![
+        numbered("1", "2023-10-01", dec!(100)),
+        other_series,
+        numbered("2", "2023-10-02", dec!(200)),
+    ];
+
+    assert!(codes(&invoices).is_empty());
+}
+
+#[test]
+fn test_same_content_under_different_numbers() {
+    let invoices = vec![
+        numbered("1", "2023-10-01", dec!(100)),
+        numbered("2", "2023-10-01", dec!(100)),
+    ];
+
+    assert_eq!(codes(&invoices), vec!["BOOK-005"]);
+}
+
+#[test]
+fn test_content_fingerprint_is_pinned() {
+    // Stored in the validation log: a change here makes every logged invoice look edited (HIST-001)
+    let invoice = load_sample("valid_invoice.xml");
+    assert_eq!(BookRules::content_fingerprint(&invoice), "8bf6ba83df2c87d0a82f7193f965976a87aabf51fbc5333eb0d6c9f12f70602b");
+
+    // The identity and the amounts' scale are not content
+    let mut renumbered = invoice.clone();
+    renumbered.header.aa = "999".to_string();
+    renumbered.uid = Some("ABC123".to_string());
+    renumbered.lines[0].net_value.rescale(4);
+    assert_eq!(BookRules::content_fingerprint(&renumbered), BookRules::content_fingerprint(&invoice));
+}
+
+#[test]
+fn test_uid_is_parsed() {
+    let xml_content = fs::read_to_string("tests/samples/valid_invoice.xml")
+        .expect("Failed to read sample file")
+        .replacen("<ns0:invoice>", "<ns0:invoice>\n    <ns0:uid>ABC123</ns0:uid>", 1);
+    let book: AadeBook = from_str(&xml_content).expect("Failed to parse XML");
+    let invoice = Normalizer::normalize(book.invoices.into_iter().next().unwrap()).expect("Normalization failed");
+
+    assert_eq!(invoice.uid.as_deref(), Some("ABC123"));
+}
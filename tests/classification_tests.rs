@@ -0,0 +1,103 @@
+use aade_validator::domain::classification_matrix::ClassificationMatrix;
+use aade_validator::domain::enums::{InvoiceType, VatCategory};
+use aade_validator::domain::invoice::Invoice;
+use aade_validator::validation::business_rules::BusinessRules;
+use aade_validator::validation::result::FixAction;
+use aade_validator::xml::normalizer::Normalizer;
+use aade_validator::xml::parser::AadeBook;
+use quick_xml::de::from_str;
+use std::fs;
+
+fn load_sample(name: &str) -> Invoice {
+    let xml_content = fs::read_to_string(format!("tests/samples/{}", name))
+        .expect("Failed to read sample file");
+    let book: AadeBook = from_str(&xml_content).expect("Failed to parse XML");
+    Normalizer::normalize(book.invoices.into_iter().next().unwrap()).expect("Normalization failed")
+}
+
+fn classify(invoice: &mut Invoice, classification_type: Option<&str>, category: Option<&str>) {
+    let classification = &mut invoice.income_classifications[0];
+    classification.classification_type = classification_type.map(str::to_string);
+    classification.classification_category = category.map(str::to_string);
+}
+
+fn matrix_codes(invoice: &Invoice) -> Vec<String> {
+    BusinessRules::validate(invoice)
+        .errors
+        .into_iter()
+        .filter(|e| matches!(e.code.as_str(), "BR-010" | "BR-011" | "BR-CLS-PAIR"))
+        .map(|e| e.code)
+        .collect()
+}
+
+#[test]
+fn test_matrix_lists_alternatives() {
+    let matrix = ClassificationMatrix::get();
+    let domestic = vec!["1".to_string()];
+
+    assert!(matrix.covers("1.1"));
+    assert!(!matrix.covers("3.1"));
+    assert_eq!(matrix.allowed_types("1.2", &domestic, "category1_1"), vec!["E3_561_005"]);
+    assert!(matrix.allowed_categories("11.2", &domestic).iter().all(|c| c == "category1_3" || c == "category1_95"));
+}
+
+#[test]
+fn test_invalid_category_suggests_valid_ones() {
+    let invoice = load_sample("i10_BR-010_invalid_e3_category.xml");
+    let report = BusinessRules::validate(&invoice);
+
+    let finding = report.errors.iter().find(|e| e.code == "BR-010").expect("Missing BR-010");
+    assert_eq!(finding.field.as_deref(), Some("incomeClassification[1].classificationCategory"));
+    let allowed = finding.allowed_values.as_ref().expect("Missing allowed values");
+    assert!(allowed.contains(&"category1_1".to_string()));
+
+    let hint = report.suggestions.iter().find(|h| h.code == "BR-010").expect("Missing BR-010 hint");
+    assert_eq!(hint.action, FixAction::Replace);
+    assert_eq!(hint.allowed_values.as_ref(), Some(allowed));
+}
+
+#[test]
+fn test_missing_category() {
+    let invoice = load_sample("i11_BR-011_e3_missing_category.xml");
+
+    assert_eq!(matrix_codes(&invoice), vec!["BR-011"]);
+}
+
+#[test]
+fn test_type_must_fit_category() {
+    let mut invoice = load_sample("v01_simple_invoice_1.1.xml");
+    assert!(matrix_codes(&invoice).is_empty());
+
+    // Retail type on a sales invoice
+    classify(&mut invoice, Some("E3_561_003"), Some("category1_1"));
+    let report = BusinessRules::validate(&invoice);
+    let finding = report.errors.iter().find(|e| e.code == "BR-CLS-PAIR").expect("Missing BR-CLS-PAIR");
+    assert_eq!(finding.field.as_deref(), Some("incomeClassification[1].classificationType"));
+    assert_eq!(finding.allowed_values, Some(vec!["E3_561_001".to_string(), "E3_561_002".to_string(), "E3_561_007".to_string()]));
+
+    // A single alternative is proposed outright
+    classify(&mut invoice, Some("E3_561_001"), Some("category1_4"));
+    let report = BusinessRules::validate(&invoice);
+    let hint = report.suggestions.iter().find(|h| h.code == "BR-CLS-PAIR").expect("Missing BR-CLS-PAIR hint");
+    assert_eq!(hint.proposed_value.as_deref(), Some("E3_880_001"));
+
+    // category1_95 takes no type, the others need one
+    classify(&mut invoice, None, Some("category1_95"));
+    assert!(matrix_codes(&invoice).is_empty());
+    classify(&mut invoice, None, Some("category1_1"));
+    assert_eq!(matrix_codes(&invoice), vec!["BR-CLS-PAIR"]);
+}
+
+#[test]
+fn test_vat_treatment_selects_credit_note_types() {
+    let mut invoice = load_sample("v01_simple_invoice_1.1.xml");
+    invoice.header.invoice_type = InvoiceType::CreditNote;
+    classify(&mut invoice, Some("E3_561_005"), Some("category1_1"));
+
+    // Intra-community types only go with zero-rated lines
+    assert_eq!(matrix_codes(&invoice), vec!["BR-CLS-PAIR"]);
+    for line in &mut invoice.lines {
+        line.vat_category = VatCategory::Vat0;
+    }
+    assert!(matrix_codes(&invoice).is_empty());
+}
@@ -0,0 +1,143 @@
+use aade_validator::domain::enums::{InvoiceType, VatCategory};
+use aade_validator::i18n::lang::Lang;
+use aade_validator::domain::invoice::{Counterpart, Invoice};
+use aade_validator::validation::business_rules::{BusinessRules, ValidationContext};
+use aade_validator::validation::correlation_rules::ReferencedInvoice;
+use aade_validator::xml::normalizer::Normalizer;
+use aade_validator::xml::parser::AadeBook;
+use quick_xml::de::from_str;
+use rust_decimal_macros::dec;
+use std::fs;
+
+fn load_sample(name: &str) -> Invoice {
+    let xml_content = fs::read_to_string(format!("tests/samples/{}", name))
+        .expect("Failed to read sample file");
+    let book: AadeBook = from_str(&xml_content).expect("Failed to parse XML");
+    Normalizer::normalize(book.invoices.into_iter().next().unwrap()).expect("Normalization failed")
+}
+
+// The i15 credit note (50 + 12 VAT, category 1, counterpart 094014201) pointing at one MARK
+fn credit_note(mark: &str) -> Invoice {
+    let mut invoice = load_sample("i15_BR-018_credit_note_no_reference.xml");
+    invoice.counterpart = Some(Counterpart { vat_number: "094014201".to_string(), country: "GR".to_string(), branch: 0, name: None });
+    invoice.header.correlated_invoices = vec![mark.to_string()];
+    invoice
+}
+
+fn original(mark: &str) -> ReferencedInvoice {
+    ReferencedInvoice {
+        mark: mark.to_string(),
+        counterpart_vat: Some("094014201".to_string()),
+        total_net_amount: dec!(100),
+        total_vat_amount: dec!(24),
+        vat_categories: vec!["1".to_string()],
+    }
+}
+
+fn correlation_codes(invoice: &Invoice, referenced: &[ReferencedInvoice]) -> Vec<String> {
+    let context = ValidationContext { referenced, ..Default::default() };
+    BusinessRules::validate_in_context(invoice, &context)
+        .errors
+        .into_iter()
+        .filter(|e| e.code == "BR-018" || e.code.starts_with("CORR-"))
+        .map(|e| e.code)
+        .collect()
+}
+
+#[test]
+fn test_credit_note_requires_references() {
+    let invoice = load_sample("i15_BR-018_credit_note_no_reference.xml");
+
+    assert_eq!(correlation_codes(&invoice, &[]), vec!["BR-018"]);
+}
+
+#[test]
+fn test_correlated_invoices_and_mark_are_parsed() {
+    let xml_content = fs::read_to_string("tests/samples/i15_BR-018_credit_note_no_reference.xml")
+        .expect("Failed to read sample file")
+        .replacen("<invoice>", "<invoice>\n    <mark>400001900000001</mark>", 1)
+        .replacen("</uid>", "</uid>\n      <correlatedInvoices>400001800000001</correlatedInvoices>\n      <correlatedInvoices> 400001800000002 </correlatedInvoices>", 1);
+    let book: AadeBook = from_str(&xml_content).expect("Failed to parse XML");
+    let invoice = Normalizer::normalize(book.invoices.into_iter().next().unwrap()).expect("Normalization failed");
+
+    assert_eq!(invoice.mark.as_deref(), Some("400001900000001"));
+    assert_eq!(invoice.header.correlated_invoices, vec!["400001800000001", "400001800000002"]);
+    assert!(correlation_codes(&invoice, &[]).is_empty());
+}
+
+#[test]
+fn test_unknown_reference_is_not_checked() {
+    let invoice = credit_note("400001800000001");
+
+    assert!(correlation_codes(&invoice, &[original("400001800000999")]).is_empty());
+}
+
+#[test]
+fn test_matching_original_is_clean() {
+    let invoice = credit_note("400001800000001");
+
+    assert!(correlation_codes(&invoice, &[original("400001800000001")]).is_empty());
+}
+
+#[test]
+fn test_counterpart_must_match_original() {
+    let invoice = credit_note("400001800000001");
+    let mut referenced = original("400001800000001");
+    referenced.counterpart_vat = Some("090000045".to_string());
+
+    assert_eq!(correlation_codes(&invoice, &[referenced]), vec!["CORR-001"]);
+}
+
+#[test]
+fn test_credit_cannot_exceed_original() {
+    let invoice = credit_note("400001800000001");
+    let mut referenced = original("400001800000001");
+    referenced.total_net_amount = dec!(40);
+    referenced.total_vat_amount = dec!(9.60);
+
+    // Both the net and the VAT credit exceed the original
+    assert_eq!(correlation_codes(&invoice, &[referenced]), vec!["CORR-002", "CORR-002"]);
+}
+
+#[test]
+fn test_service_credit_cannot_exceed_original() {
+    let mut invoice = credit_note("400001800000001");
+    invoice.header.invoice_type = InvoiceType::ServiceCreditNote;
+    let mut referenced = original("400001800000001");
+    referenced.total_net_amount = dec!(40);
+    referenced.total_vat_amount = dec!(9.60);
+
+    assert_eq!(correlation_codes(&invoice, &[referenced]), vec!["CORR-002", "CORR-002"]);
+}
+
+#[test]
+fn test_vat_category_finding_names_the_line_it_points_at() {
+    let mut invoice = credit_note("400001800000001");
+    invoice.lines[0].vat_category = VatCategory::Vat13;
+    invoice.lines[0].line_number = 10;
+    let context = ValidationContext { referenced: &[original("400001800000001")], ..Default::default() };
+    let mut report = BusinessRules::validate_in_context(&invoice, &context);
+    report.localize(Lang::En);
+
+    let finding = report.errors.iter().find(|e| e.code == "CORR-003").expect("Missing CORR-003");
+    assert_eq!(finding.field.as_deref(), Some("line[1].vatCategory"));
+    assert!(finding.reason.starts_with("Line 1:"), "{}", finding.reason);
+}
+
+#[test]
+fn test_vat_categories_must_match_original() {
+    let mut invoice = credit_note("400001800000001");
+    invoice.lines[0].vat_category = VatCategory::Vat13;
+
+    assert_eq!(correlation_codes(&invoice, &[original("400001800000001")]), vec!["CORR-003"]);
+}
+
+#[test]
+fn test_document_invoice_can_be_referenced() {
+    let mut original_invoice = load_sample("valid_invoice.xml");
+    assert!(ReferencedInvoice::from_invoice(&original_invoice).is_none());
+
+    original_invoice.mark = Some("400001800000001".to_string());
+    let referenced = ReferencedInvoice::from_invoice(&original_invoice).unwrap();
+    assert_eq!(referenced.total_net_amount, original_invoice.totals.total_net_amount);
+}
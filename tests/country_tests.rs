@@ -0,0 +1,105 @@
+use aade_validator::domain::country::{CountryCatalog, Supply, VatRegion};
+use aade_validator::domain::enums::InvoiceType;
+use aade_validator::domain::invoice::Invoice;
+use aade_validator::validation::business_rules::BusinessRules;
+use aade_validator::xml::normalizer::Normalizer;
+use aade_validator::xml::parser::AadeBook;
+use chrono::NaiveDate;
+use quick_xml::de::from_str;
+use std::fs;
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+// The intra-EU example invoice, sold to a Northern Ireland business under the given type
+fn to_northern_ireland(invoice_type: InvoiceType) -> Invoice {
+    let xml_content = fs::read_to_string("tests/rule_examples/intra_eu_sale_de.xml")
+        .expect("Failed to read example file");
+    let book: AadeBook = from_str(&xml_content).expect("Failed to parse XML");
+    let mut invoice = Normalizer::normalize(book.invoices.into_iter().next().unwrap()).expect("Normalization failed");
+    invoice.header.invoice_type = invoice_type;
+    let counterpart = invoice.counterpart.as_mut().unwrap();
+    counterpart.country = "XI".to_string();
+    counterpart.vat_number = "980780684".to_string();
+    invoice
+}
+
+fn region_codes(invoice: &Invoice) -> Vec<String> {
+    BusinessRules::validate(invoice).errors.into_iter()
+        .map(|e| e.code)
+        .filter(|c| c == "CP-002" || c == "CP-003")
+        .collect()
+}
+
+#[test]
+fn test_catalogue_covers_iso_and_vat_codes() {
+    let catalog = CountryCatalog::get();
+
+    assert_eq!(catalog.name("DE"), Some("Germany"));
+    assert!(catalog.is_valid("XI"));
+    assert!(catalog.is_valid("EL"));
+    assert!(!catalog.is_valid("XX"));
+    assert!(!catalog.is_valid("gr"));
+}
+
+#[test]
+fn test_brexit_is_dated() {
+    let catalog = CountryCatalog::get();
+
+    assert_eq!(catalog.vat_region("GB", date("2020-12-31")), Some(VatRegion::EuMember));
+    assert_eq!(catalog.vat_region("GB", date("2021-01-01")), Some(VatRegion::ThirdCountry));
+    assert_eq!(catalog.vat_region("XI", date("2021-01-01")), Some(VatRegion::EuMember));
+    assert_eq!(catalog.vat_region("HR", date("2013-06-30")), Some(VatRegion::ThirdCountry));
+}
+
+#[test]
+fn test_northern_ireland_is_eu_for_goods_only() {
+    let catalog = CountryCatalog::get();
+    let today = date("2025-01-20");
+
+    assert_eq!(catalog.vat_region_for("XI", today, Supply::Goods), Some(VatRegion::EuMember));
+    assert_eq!(catalog.vat_region_for("XI", today, Supply::Services), Some(VatRegion::ThirdCountry));
+    assert_eq!(catalog.vat_region_for("IE", today, Supply::Services), Some(VatRegion::EuMember));
+}
+
+#[test]
+fn test_services_to_northern_ireland_are_third_country() {
+    // Intra-EU services are not possible to XI, services to a third country are
+    assert_eq!(region_codes(&to_northern_ireland(InvoiceType::ServiceInvoiceIntra)), vec!["CP-002"]);
+    assert!(region_codes(&to_northern_ireland(InvoiceType::ServiceInvoiceThirdCountry)).is_empty());
+
+    // Goods keep moving within the EU VAT area
+    assert!(region_codes(&to_northern_ireland(InvoiceType::SalesInvoiceIntra)).is_empty());
+    assert_eq!(region_codes(&to_northern_ireland(InvoiceType::SalesInvoiceThirdCountry)), vec!["CP-003"]);
+}
+
+#[test]
+fn test_special_territories() {
+    let catalog = CountryCatalog::get();
+    let today = date("2025-01-20");
+
+    assert_eq!(catalog.vat_region("GR", today), Some(VatRegion::Domestic));
+    assert_eq!(catalog.vat_region("IC", today), Some(VatRegion::ThirdCountry));
+    assert_eq!(catalog.vat_region("AX", today), Some(VatRegion::ThirdCountry));
+    assert_eq!(catalog.vat_region("MC", today), Some(VatRegion::EuMember));
+    assert_eq!(catalog.vat_region("XX", today), None);
+
+    let greek: Vec<&str> = catalog.territories_of("GR").map(|t| t.name.as_str()).collect();
+    assert_eq!(greek, vec!["Mount Athos"]);
+    assert!(catalog.territories_of("DE").any(|t| t.name.starts_with("Büsingen")));
+}
+
+#[test]
+fn test_invalid_country_code_is_flagged() {
+    let xml_content = fs::read_to_string("tests/samples/valid_invoice.xml")
+        .expect("Failed to read sample file");
+    let book: AadeBook = from_str(&xml_content).expect("Failed to parse XML");
+    let mut invoice = Normalizer::normalize(book.invoices.into_iter().next().unwrap()).expect("Normalization failed");
+    invoice.counterpart.as_mut().unwrap().country = "GRC".to_string();
+
+    let report = BusinessRules::validate(&invoice);
+    let finding = report.errors.iter().find(|e| e.code == "BR-COUNTRY").expect("Missing BR-COUNTRY");
+    assert_eq!(finding.field.as_deref(), Some("counterpart.country"));
+    assert_eq!(finding.value_found.as_deref(), Some("GRC"));
+}
@@ -1,7 +1,11 @@
 use aade_validator::xml::parser::AadeBook;
 use aade_validator::xml::normalizer::Normalizer;
-use aade_validator::validation::diff;
+use aade_validator::validation::diff::{self, ChangeKind, DiffValue};
+use aade_validator::domain::classification::IncomeClassification;
+use aade_validator::domain::invoice::{Counterpart, Invoice};
+use chrono::NaiveDate;
 use quick_xml::de::from_str;
+use rust_decimal_macros::dec;
 
 #[test]
 fn test_diff_detection() {
@@ -54,12 +58,260 @@ fn test_diff_detection() {
     assert!(report.has_changes);
     
     // Check for VAT change
-    let vat_change = report.changes.iter().any(|c| c.path == "issuer.vat_number" && c.new_value == "999999999");
+    let vat_change = report.changes.iter()
+        .any(|c| c.path == "issuer.vat_number" && c.new_value == Some(DiffValue::Text("999999999".to_string())));
     assert!(vat_change, "Should detect issuer VAT change");
 
     // Check for Net Value change on line 1
-    let line_change = report.changes.iter().any(|c| c.path == "line[1].net_value" && c.new_value == "200");
+    let line_change = report.changes.iter()
+        .any(|c| c.path == "lines[1].net_value" && c.new_value == Some(DiffValue::Decimal(dec!(200))));
     assert!(line_change, "Should detect net value change on line 1");
 
     println!("Diff detected successfully: {:#?}", report.changes);
 }
+
+fn sample() -> Invoice {
+    let xml = std::fs::read_to_string("tests/samples/v01_simple_invoice_1.1.xml").expect("Failed to read sample file");
+    let book: AadeBook = from_str(&xml).unwrap();
+    Normalizer::normalize(book.invoices.into_iter().next().unwrap()).unwrap()
+}
+
+#[test]
+fn test_identical_invoices_have_no_changes() {
+    let invoice = sample();
+    let mut rescaled = invoice.clone();
+    rescaled.totals.total_net_amount = rescaled.totals.total_net_amount.round_dp(4);
+
+    assert!(!diff::compare(&invoice, &rescaled).has_changes, "100 and 100.0000 are the same amount");
+}
+
+#[test]
+fn test_every_field_is_compared_with_typed_values() {
+    let old = sample();
+    let mut new = old.clone();
+    new.header.issue_date = NaiveDate::from_ymd_opt(2025, 2, 1).unwrap();
+    new.header.currency = "USD".to_string();
+    new.lines[0].vat_amount += dec!(1);
+    new.totals.total_fees_amount = dec!(5);
+    new.income_classifications.push(IncomeClassification {
+        classification_type: Some("E3_561_001".to_string()),
+        classification_category: Some("category1_1".to_string()),
+        amount: dec!(10),
+    });
+
+    let report = diff::compare(&old, &new);
+    let change = |path: &str| report.changes.iter().find(|c| c.path == path).unwrap_or_else(|| panic!("{} not reported: {:#?}", path, report.changes));
+
+    assert_eq!(change("header.issue_date").new_value, Some(DiffValue::Date(new.header.issue_date)));
+    assert_eq!(change("header.currency").old_value, Some(DiffValue::Text("EUR".to_string())));
+    assert_eq!(change("lines[1].vat_amount").kind, ChangeKind::Modified);
+    assert_eq!(change("totals.total_fees_amount").new_value, Some(DiffValue::Decimal(dec!(5))));
+
+    let added = format!("income_classifications[{}].classificationType", new.income_classifications.len());
+    assert_eq!(change(&added).kind, ChangeKind::Added);
+}
+
+#[test]
+fn test_optional_sections_are_added_and_removed_field_by_field() {
+    let mut old = sample();
+    old.counterpart = None;
+    let mut new = old.clone();
+    new.counterpart = Some(Counterpart { vat_number: "094014201".to_string(), country: "GR".to_string(), branch: 2, name: None });
+    new.header.invoice_type = serde_json::from_value(serde_json::json!("2.1")).unwrap();
+
+    let report = diff::compare(&old, &new);
+    let branch = report.changes.iter().find(|c| c.path == "counterpart.branch").expect("Missing counterpart.branch");
+    assert_eq!(branch.kind, ChangeKind::Added);
+    assert_eq!(branch.new_value, Some(DiffValue::Integer(2)));
+    assert!(!report.changes.iter().any(|c| c.path == "counterpart.name"), "Unset options are not changes");
+
+    let invoice_type = report.changes.iter().find(|c| c.path == "header.invoice_type").expect("Missing header.invoice_type");
+    assert_eq!(invoice_type.new_value, Some(DiffValue::Enum("2.1".to_string())));
+
+    let report = diff::compare(&new, &old);
+    assert!(report.changes.iter().any(|c| c.path == "counterpart.vat_number" && c.kind == ChangeKind::Removed));
+}
+
+#[test]
+fn test_documents_are_paired_by_uid_then_series_and_aa() {
+    let mut renumbered = sample();
+    renumbered.uid = Some("UID-1".to_string());
+    let mut by_number = sample();
+    by_number.uid = None;
+    by_number.header.aa = "7".to_string();
+    let mut dropped = by_number.clone();
+    dropped.header.aa = "8".to_string();
+
+    let mut renumbered_fixed = renumbered.clone();
+    renumbered_fixed.header.aa = "99".to_string();
+    let mut by_number_fixed = by_number.clone();
+    by_number_fixed.totals.total_fees_amount = dec!(1);
+    let mut added = by_number.clone();
+    added.header.aa = "9".to_string();
+
+    let report = diff::compare_documents(
+        &[renumbered, by_number, dropped],
+        &[added, by_number_fixed, renumbered_fixed],
+    );
+
+    assert_eq!(report.pairs.len(), 2);
+    assert_eq!(report.pairs[0].matched_by, diff::MatchedBy::Uid);
+    assert!(report.pairs[0].diff.changes.iter().any(|c| c.path == "header.aa"));
+    assert_eq!(report.pairs[1].matched_by, diff::MatchedBy::SeriesAa);
+    assert_eq!(report.pairs[1].diff.changes.len(), 1);
+
+    assert_eq!(report.only_in_original.iter().map(|r| r.aa.as_str()).collect::<Vec<_>>(), vec!["8"]);
+    assert_eq!(report.only_in_corrected.iter().map(|r| r.aa.as_str()).collect::<Vec<_>>(), vec!["9"]);
+}
+
+#[tokio::test]
+async fn test_diff_endpoint_accepts_json() {
+    use aade_validator::api::diff::diff_documents;
+    use axum::body::{to_bytes, Body};
+    use axum::http::{header, Request, StatusCode};
+
+    let original = std::fs::read_to_string("tests/samples/v01_simple_invoice_1.1.xml").unwrap();
+    let corrected = original.replace("<series>", "<series>B");
+    let body = serde_json::json!({ "original": original, "corrected": corrected });
+    let request = Request::builder()
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+
+    let response = diff_documents(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let json: serde_json::Value = serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+    let pair = &json["pairs"][0];
+    assert_eq!(pair["matched_by"], "uid", "{}", json);
+    assert_eq!(pair["diff"]["changes"][0]["path"], "header.series");
+    assert_eq!(pair["diff"]["changes"][0]["new_value"]["type"], "text");
+
+    let request = Request::builder()
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(serde_json::json!({ "original": "<broken", "corrected": corrected }).to_string()))
+        .unwrap();
+    assert_eq!(diff_documents(request).await.status(), StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn test_policy_drives_the_verdict() {
+    use aade_validator::validation::diff_policy::{ChangeSeverity, DiffVerdict, RecommendedAction};
+
+    let old = sample();
+    assert_eq!(diff::compare(&old, &old).verdict, DiffVerdict::Unchanged);
+
+    let verdict = |edit: &dyn Fn(&mut Invoice)| {
+        let mut new = old.clone();
+        edit(&mut new);
+        diff::compare(&old, &new).verdict
+    };
+    assert_eq!(verdict(&|i| i.lines[0].description = "Consulting".to_string()), DiffVerdict::Cosmetic);
+    assert_eq!(verdict(&|i| i.totals.total_vat_amount += dec!(0.01)), DiffVerdict::Cosmetic, "Rounding is tolerated");
+    assert_eq!(verdict(&|i| i.income_classifications.clear()), DiffVerdict::Correctable);
+    assert_eq!(verdict(&|i| i.header.aa = "2".to_string()), DiffVerdict::RequiresCancellation);
+    assert_eq!(verdict(&|i| i.totals.total_vat_amount += dec!(1)), DiffVerdict::RequiresCreditNote);
+    assert_eq!(verdict(&|i| i.counterpart.as_mut().unwrap().vat_number = "999999999".to_string()), DiffVerdict::RequiresCreditNote);
+
+    // The heaviest change decides, each change keeps its own outcome
+    let mut new = old.clone();
+    new.header.aa = "2".to_string();
+    new.lines[0].net_value += dec!(10);
+    let report = diff::compare(&old, &new);
+    assert_eq!(report.verdict, DiffVerdict::RequiresCreditNote);
+    let aa = report.changes.iter().find(|c| c.path == "header.aa").unwrap();
+    assert_eq!((aa.severity, aa.action), (ChangeSeverity::Warning, RecommendedAction::CancelAndReissue));
+    let net = report.changes.iter().find(|c| c.path == "lines[1].net_value").unwrap();
+    assert_eq!((net.severity, net.action), (ChangeSeverity::Critical, RecommendedAction::CreditNote));
+}
+
+#[test]
+fn test_custom_policy_table() {
+    use aade_validator::validation::diff_policy::{DiffPolicy, DiffVerdict};
+
+    let policy = DiffPolicy::load_from_yaml(r#"
+rules:
+  - { paths: ["lines[*].**"], max_delta: "5", severity: info, action: none }
+default: { severity: critical, action: credit_note }
+"#).expect("Invalid policy");
+
+    let old = sample();
+    let mut new = old.clone();
+    new.lines[0].net_value += dec!(5);
+    assert_eq!(diff::compare_with_policy(&old, &new, &policy).verdict, DiffVerdict::Cosmetic);
+
+    new.lines[0].net_value += dec!(1);
+    assert_eq!(diff::compare_with_policy(&old, &new, &policy).verdict, DiffVerdict::RequiresCreditNote);
+}
+
+// The sample with one line per (description, net value), numbered in order
+fn with_lines(lines: &[(&str, rust_decimal::Decimal)]) -> Invoice {
+    let mut invoice = sample();
+    let template = invoice.lines[0].clone();
+    invoice.lines = lines.iter().enumerate().map(|(idx, (description, net))| {
+        let mut line = template.clone();
+        line.line_number = idx as i32 + 1;
+        line.description = description.to_string();
+        line.net_value = *net;
+        line.vat_amount = *net * dec!(0.24);
+        line
+    }).collect();
+    invoice
+}
+
+#[test]
+fn test_inserted_line_does_not_shift_the_others() {
+    use aade_validator::validation::diff_policy::DiffVerdict;
+
+    let old = with_lines(&[("Laptop", dec!(900)), ("Mouse", dec!(20)), ("Monitor", dec!(250))]);
+    let new = with_lines(&[("Laptop", dec!(900)), ("Keyboard", dec!(45)), ("Mouse", dec!(20)), ("Monitor", dec!(250))]);
+    let report = diff::compare(&old, &new);
+
+    // Only the new line is a change, the later ones are renumbered
+    assert!(report.changes.iter().all(|c| c.path.starts_with("lines[2].") && c.kind == ChangeKind::Added), "{:#?}", report.changes);
+    let monitor = report.line_matches.iter().find(|m| m.original_position == 3).expect("Monitor not matched");
+    assert_eq!((monitor.corrected_position, monitor.corrected_line_number), (4, 4));
+    assert!(monitor.renumbered && !monitor.moved);
+    assert_eq!(monitor.confidence, 1.0);
+    assert_eq!(report.verdict, DiffVerdict::RequiresCreditNote, "A new line changes the amounts");
+}
+
+#[test]
+fn test_moved_lines_are_not_value_changes() {
+    use aade_validator::validation::diff_policy::DiffVerdict;
+
+    let old = with_lines(&[("Laptop", dec!(900)), ("Mouse", dec!(20)), ("Monitor", dec!(250))]);
+    let new = with_lines(&[("Monitor", dec!(250)), ("Laptop", dec!(900)), ("Mouse", dec!(20))]);
+    let report = diff::compare(&old, &new);
+
+    assert!(report.changes.is_empty(), "{:#?}", report.changes);
+    assert!(report.has_changes);
+    assert_eq!(report.verdict, DiffVerdict::Cosmetic);
+    let moved: Vec<usize> = report.line_matches.iter().filter(|m| m.moved).map(|m| m.original_position).collect();
+    assert_eq!(moved, vec![3], "Moving the monitor up is one move, not three");
+}
+
+#[test]
+fn test_changed_amount_keeps_the_line_matched() {
+    let old = with_lines(&[("Laptop", dec!(900)), ("Mouse", dec!(20))]);
+    let new = with_lines(&[("Laptop", dec!(850)), ("Mouse", dec!(20))]);
+    let report = diff::compare(&old, &new);
+
+    let laptop = &report.line_matches[0];
+    assert_eq!((laptop.original_position, laptop.corrected_position), (1, 1));
+    assert!(laptop.confidence < 1.0 && laptop.confidence > 0.5, "{}", laptop.confidence);
+    assert!(report.changes.iter().any(|c| c.path == "lines[1].net_value" && c.kind == ChangeKind::Modified));
+}
+
+#[test]
+fn test_removed_line_does_not_share_a_path_with_a_match() {
+    let old = with_lines(&[("Laptop", dec!(900)), ("Mouse", dec!(20))]);
+    let new = with_lines(&[("Mouse", dec!(22))]);
+    let report = diff::compare(&old, &new);
+
+    // The laptop was line 1 and the mouse is line 1 now: one is removed, the other modified
+    let removed: Vec<&str> = report.changes.iter().filter(|c| c.kind == ChangeKind::Removed).map(|c| c.path.as_str()).collect();
+    assert!(!removed.is_empty() && removed.iter().all(|p| p.starts_with("lines[-1].")), "{:#?}", report.changes);
+    let modified: Vec<&str> = report.changes.iter().filter(|c| c.kind == ChangeKind::Modified).map(|c| c.path.as_str()).collect();
+    assert!(modified.contains(&"lines[1].net_value"), "{:#?}", report.changes);
+    assert!(modified.iter().all(|p| !removed.contains(p)));
+}
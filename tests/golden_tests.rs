@@ -0,0 +1,131 @@
+use aade_validator::validation::business_rules::BusinessRules;
+use aade_validator::validation::result::Severity;
+use aade_validator::xml::normalizer::Normalizer;
+use aade_validator::xml::parser::AadeBook;
+use quick_xml::de::from_str;
+use std::collections::BTreeSet;
+use std::fs;
+
+/// Extracts the expected error codes from a golden filename:
+/// `iNN_<CODE>_description.xml` expects `<CODE>`, `vNN_*.xml` expects none.
+fn expected_codes(filename: &str) -> Option<BTreeSet<String>> {
+    let name = filename.strip_prefix("FIXED_").unwrap_or(filename);
+    let mut parts = name.split('_');
+    let prefix = parts.next()?;
+    let number = prefix.get(1..)?;
+    if number.len() != 2 || !number.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    match prefix.chars().next()? {
+        'v' => Some(BTreeSet::new()),
+        'i' => Some(parts.next().map(|code| BTreeSet::from([code.to_string()]))?),
+        _ => None,
+    }
+}
+
+fn actual_codes(content: &str) -> Result<BTreeSet<String>, String> {
+    let book: AadeBook = from_str(content).map_err(|e| e.to_string())?;
+    let mut codes = BTreeSet::new();
+
+    for xml_inv in book.invoices {
+        let invoice = Normalizer::normalize(xml_inv)?;
+        let report = BusinessRules::validate(&invoice);
+        codes.extend(
+            report.errors.into_iter()
+                .filter(|e| e.severity == Severity::Error)
+                .map(|e| e.code),
+        );
+    }
+
+    Ok(codes)
+}
+
+/// Checks a sample against the codes its filename names, or against `pinned`
+/// when the sample also carries defects its name does not mention.
+fn assert_golden(filename: &str, pinned: Option<&[&str]>) {
+    let expected = match pinned {
+        Some(codes) => codes.iter().map(|c| c.to_string()).collect(),
+        None => expected_codes(filename).expect("Not a golden sample name"),
+    };
+    let content = fs::read_to_string(format!("tests/samples/{}", filename)).unwrap();
+    assert_eq!(actual_codes(&content), Ok(expected), "{}", filename);
+}
+
+/// One test per sample. A case may pin its exact codes (`=> "file" ["CODE", ...]`)
+/// when the sample is not as clean as its name says; the reason goes above it.
+macro_rules! golden_samples {
+    (@pinned) => { None };
+    (@pinned [$($code:literal),*]) => { Some(&[$($code),*][..]) };
+    ($($name:ident => $file:literal $([$($code:literal),*])?,)*) => {
+        const SAMPLES: &[&str] = &[$($file),*];
+
+        $(
+            #[test]
+            fn $name() {
+                assert_golden($file, golden_samples!(@pinned $([$($code),*])?));
+            }
+        )*
+    };
+}
+
+golden_samples! {
+    // i01-i15 issue a 1.1 without a counterpart, so CP-001 fires too; i16-i23 are the clean variants
+    i01_net_mismatch => "i01_BR-001_net_mismatch.xml" ["BR-001", "CP-001"],
+    i02_vat_mismatch => "i02_BR-002_vat_mismatch.xml" ["BR-002", "CP-001"],
+    // Issuer AFM 090000045 has a valid check digit, see i18
+    i03_invalid_issuer_afm => "i03_BR-003_invalid_issuer_afm.xml" ["CP-001"],
+    // Counterpart AFM 094014201 has a valid check digit, see i19
+    i04_invalid_counterpart_afm => "i04_BR-004_invalid_counterpart_afm.xml" [],
+    i05_future_date => "i05_BR-005_future_date.xml" ["BR-005", "CP-001"],
+    // uid is optional in myDATA
+    i06_missing_uid => "i06_BR-006_missing_uid.xml" ["CP-001"],
+    i07_gross_formula_wrong => "i07_BR-007_gross_formula_wrong.xml" ["BR-007", "CP-001"],
+    // InvoiceType does not model every myDATA type yet (e.g. 3.1 in v05), so an
+    // unknown type cannot be told apart from a valid one and is not rejected
+    i08_invalid_invoice_type => "i08_BR-008_invalid_invoice_type.xml" [],
+    // A missing classification is reported as CLS-001 and an unmatched total
+    i09_missing_e3_mandatory => "i09_BR-009_missing_e3_mandatory.xml" ["BR-CLS-TOTAL", "CLS-001", "CP-001"],
+    i10_invalid_e3_category => "i10_BR-010_invalid_e3_category.xml" ["BR-010", "CP-001"],
+    i11_e3_missing_category => "i11_BR-011_e3_missing_category.xml" ["BR-011", "CP-001"],
+    // A negative classification amount no longer adds up to the net value
+    i12_e3_negative_amount => "i12_BR-012_e3_negative_amount.xml" ["BR-CLS-TOTAL", "CP-001"],
+    i13_invalid_payment_type => "i13_BR-014_invalid_payment_type.xml" ["BR-014", "CP-001"],
+    // Reported as CUR-001; the 1.2 also charges 24% with a domestic E3 type
+    i14_missing_exchange_rate => "i14_BR-016_missing_exchange_rate.xml" ["BR-CLS-PAIR", "CP-001", "CUR-001", "VAT-002"],
+    i15_credit_note_no_reference => "i15_BR-018_credit_note_no_reference.xml" ["BR-018", "CP-001"],
+    i16_net_mismatch => "i16_BR-001_net_mismatch_with_counterpart.xml",
+    i17_vat_mismatch => "i17_BR-002_vat_mismatch_with_counterpart.xml",
+    i18_issuer_afm_bad_check_digit => "i18_BR-003_issuer_afm_bad_check_digit.xml",
+    i19_counterpart_afm_bad_check_digit => "i19_BR-004_counterpart_afm_bad_check_digit.xml",
+    i20_future_date => "i20_BR-005_future_date_with_counterpart.xml",
+    i21_invalid_e3_category => "i21_BR-010_invalid_e3_category_with_counterpart.xml",
+    i22_e3_missing_category => "i22_BR-011_e3_missing_category_with_counterpart.xml",
+    i23_invalid_payment_type => "i23_BR-014_invalid_payment_type_with_counterpart.xml",
+    v01_simple_invoice => "v01_simple_invoice_1.1.xml",
+    v02_multiple_lines_e3 => "v02_multiple_lines_e3.xml",
+    v03_credit_note => "v03_credit_note_5.1.xml",
+    // An intra-EU 1.2 sent to a GR buyer at 24% with a domestic E3 type; see v06 for a clean USD invoice
+    v04_foreign_currency_usd => "v04_foreign_currency_usd.xml" ["BR-CLS-PAIR", "CP-002", "VAT-002"],
+    v05_retail => "v05_retail_3.1.xml",
+    v06_foreign_currency_usd => "v06_foreign_currency_usd_1.1.xml",
+    fixed_v01_simple_invoice => "FIXED_v01_simple_invoice_1.1.xml",
+    fixed_v02_multiple_lines_e3 => "FIXED_v02_multiple_lines_e3.xml",
+    fixed_v03_credit_note => "FIXED_v03_credit_note_5.1.xml",
+    fixed_v04_foreign_currency_usd => "FIXED_v04_foreign_currency_usd.xml" ["BR-CLS-PAIR", "CP-002", "VAT-002"],
+    fixed_v05_retail => "FIXED_v05_retail_3.1.xml",
+}
+
+#[test]
+fn test_every_golden_sample_has_a_case() {
+    let mut missing: Vec<String> = fs::read_dir("tests/samples")
+        .expect("Failed to read samples directory")
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .filter(|name| name.ends_with(".xml") && expected_codes(name).is_some())
+        .filter(|name| !SAMPLES.contains(&name.as_str()))
+        .collect();
+    missing.sort();
+
+    assert!(missing.is_empty(), "Golden samples without a case: {:?}", missing);
+}
@@ -0,0 +1,92 @@
+use aade_validator::domain::invoice::Invoice;
+use aade_validator::validation::book_rules::BookRules;
+use aade_validator::validation::business_rules::{BusinessRules, ValidationContext};
+use aade_validator::validation::history_rules::SeriesHistory;
+use aade_validator::validation::result::{Severity, ValidationStatus};
+use aade_validator::xml::normalizer::Normalizer;
+use aade_validator::xml::parser::AadeBook;
+use chrono::NaiveDate;
+use quick_xml::de::from_str;
+use std::fs;
+
+fn load_sample(name: &str) -> Invoice {
+    let xml_content = fs::read_to_string(format!("tests/samples/{}", name))
+        .expect("Failed to read sample file");
+    let book: AadeBook = from_str(&xml_content).expect("Failed to parse XML");
+    Normalizer::normalize(book.invoices.into_iter().next().unwrap()).expect("Normalization failed")
+}
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn history_codes(invoice: &Invoice, history: &SeriesHistory) -> Vec<String> {
+    BusinessRules::validate_in_context(invoice, &ValidationContext { series_history: Some(history), ..Default::default() })
+        .errors
+        .into_iter()
+        .filter(|e| e.code.starts_with("HIST-"))
+        .map(|e| e.code)
+        .collect()
+}
+
+#[test]
+fn test_stateless_mode_ignores_history() {
+    let invoice = load_sample("valid_invoice.xml");
+    let report = BusinessRules::validate(&invoice);
+
+    assert_eq!(report.status, ValidationStatus::Green);
+}
+
+#[test]
+fn test_revalidating_same_content_is_clean() {
+    let invoice = load_sample("valid_invoice.xml");
+    let history = SeriesHistory {
+        same_number_hashes: vec![BookRules::content_fingerprint(&invoice)],
+        last_aa: Some(1),
+        last_date: Some(invoice.header.issue_date),
+    };
+
+    assert!(history_codes(&invoice, &history).is_empty());
+}
+
+#[test]
+fn test_known_number_with_different_content() {
+    let invoice = load_sample("valid_invoice.xml");
+    let history = SeriesHistory {
+        same_number_hashes: vec!["0".repeat(64)],
+        last_aa: Some(1),
+        last_date: Some(invoice.header.issue_date),
+    };
+    let report = BusinessRules::validate_in_context(&invoice, &ValidationContext { series_history: Some(&history), ..Default::default() });
+
+    assert_eq!(report.status, ValidationStatus::Yellow);
+    assert!(report.findings(Severity::Warning).any(|e| e.code == "HIST-001"));
+    assert!(report.risk_factors.iter().any(|f| f.code == "HIST-001"));
+}
+
+#[test]
+fn test_series_going_backwards_and_skipping_numbers() {
+    let mut invoice = load_sample("valid_invoice.xml");
+    invoice.header.aa = "10".to_string();
+    invoice.header.issue_date = date("2023-10-01");
+    let history = SeriesHistory {
+        same_number_hashes: vec![],
+        last_aa: Some(7),
+        last_date: Some(date("2023-10-15")),
+    };
+
+    assert_eq!(history_codes(&invoice, &history), vec!["HIST-002", "HIST-003"]);
+}
+
+#[test]
+fn test_next_number_in_sequence() {
+    let mut invoice = load_sample("valid_invoice.xml");
+    invoice.header.aa = "8".to_string();
+    let history = SeriesHistory {
+        same_number_hashes: vec![],
+        last_aa: Some(7),
+        last_date: Some(invoice.header.issue_date),
+    };
+
+    assert!(history_codes(&invoice, &history).is_empty());
+}
@@ -0,0 +1,83 @@
+use aade_validator::i18n::catalog::{placeholders, Catalog};
+use aade_validator::i18n::lang::Lang;
+use aade_validator::validation::business_rules::BusinessRules;
+use aade_validator::validation::result::ValidationReport;
+use aade_validator::validation::rules_engine::RulesEngine;
+use aade_validator::xml::normalizer::Normalizer;
+use aade_validator::xml::parser::AadeBook;
+use quick_xml::de::from_str;
+use std::collections::BTreeSet;
+use std::fs;
+
+#[test]
+fn test_catalogues_have_the_same_keys_and_parameters() {
+    let el = Catalog::for_lang(Lang::El);
+    let en = Catalog::for_lang(Lang::En);
+
+    let el_keys: BTreeSet<&str> = el.keys().collect();
+    let en_keys: BTreeSet<&str> = en.keys().collect();
+    assert_eq!(el_keys, en_keys, "Greek and English catalogues differ");
+
+    for key in el_keys {
+        assert_eq!(
+            placeholders(el.get(key).unwrap()),
+            placeholders(en.get(key).unwrap()),
+            "Parameters of {} differ between languages", key
+        );
+    }
+}
+
+#[test]
+fn test_every_rule_has_a_message() {
+    let yaml = fs::read_to_string("rules/mydata_v1.yaml").expect("Failed to read rules file");
+    let engine = RulesEngine::load_from_yaml(&yaml).expect("Failed to parse rules file");
+
+    for rule in engine.rules() {
+        for lang in [Lang::El, Lang::En] {
+            assert!(Catalog::for_lang(lang).get(&rule.id).is_some(), "{} has no {:?} message", rule.id, lang);
+        }
+    }
+}
+
+#[test]
+fn test_report_localizes_to_english() {
+    let xml_content = fs::read_to_string("tests/samples/invalid_invoice.xml")
+        .expect("Failed to read sample file");
+    let book: AadeBook = from_str(&xml_content).expect("Failed to parse XML");
+    let invoice = Normalizer::normalize(book.invoices.into_iter().next().unwrap()).expect("Normalization failed");
+
+    let mut report = BusinessRules::validate(&invoice);
+    let greek = report.errors.iter().find(|e| e.code == "BR-001").unwrap().reason.clone();
+
+    report.localize(Lang::En);
+    let english = &report.errors.iter().find(|e| e.code == "BR-001").unwrap().reason;
+
+    assert_ne!(&greek, english);
+    assert_eq!(english, "Calculated net amount (100) does not match the declared total (200).");
+    assert_eq!(report.summary, "Validation failed. Please correct the errors.");
+    assert!(report.suggestions.iter().any(|h| h.suggestion == "Set the total net value to the sum of the lines."));
+}
+
+#[test]
+fn test_stored_report_keeps_its_texts() {
+    let xml_content = fs::read_to_string("tests/samples/invalid_invoice.xml")
+        .expect("Failed to read sample file");
+    let book: AadeBook = from_str(&xml_content).expect("Failed to parse XML");
+    let invoice = Normalizer::normalize(book.invoices.into_iter().next().unwrap()).expect("Normalization failed");
+    let report = BusinessRules::validate(&invoice);
+
+    // As read back from the validation log or a batch job: the messages are not stored
+    let mut stored: ValidationReport = serde_json::from_value(serde_json::to_value(&report).unwrap()).unwrap();
+    stored.localize(Lang::En);
+
+    for (before, after) in report.errors.iter().zip(&stored.errors) {
+        assert!(!after.reason.is_empty());
+        assert_eq!(before.reason, after.reason);
+    }
+    for (before, after) in report.suggestions.iter().zip(&stored.suggestions) {
+        assert_eq!(before.suggestion, after.suggestion);
+    }
+    for (before, after) in report.risk_factors.iter().zip(&stored.risk_factors) {
+        assert_eq!(before.reason, after.reason);
+    }
+}
@@ -0,0 +1,112 @@
+use aade_validator::domain::enums::InvoiceType;
+use aade_validator::domain::invoice::Invoice;
+use aade_validator::i18n::lang::Lang;
+use aade_validator::validation::business_rules::{BusinessRules, ValidationContext};
+use aade_validator::validation::island_rules::IslandBranch;
+use aade_validator::validation::result::{FixAction, Severity, ValidationReport, ValidationStatus};
+use aade_validator::xml::normalizer::Normalizer;
+use aade_validator::xml::parser::AadeBook;
+use chrono::NaiveDate;
+use quick_xml::de::from_str;
+
+// Issued on 2025-01-20 from branch 1, one line at 9% (category 5)
+fn island_invoice() -> Invoice {
+    let xml = r#"<InvoicesDoc><invoice>
+  <issuer><vatNumber>090000045</vatNumber><country>GR</country><branch>1</branch></issuer>
+  <counterpart><vatNumber>094014201</vatNumber><country>GR</country><branch>0</branch></counterpart>
+  <invoiceHeader><series>A</series><aa>1</aa><issueDate>2025-01-20</issueDate><invoiceType>2.1</invoiceType></invoiceHeader>
+  <invoiceDetails><lineNumber>1</lineNumber><netValue>100.00</netValue><vatCategory>5</vatCategory><vatAmount>9.00</vatAmount></invoiceDetails>
+  <invoiceSummary><totalNetValue>100.00</totalNetValue><totalVatAmount>9.00</totalVatAmount><totalWithheldAmount>0</totalWithheldAmount><totalFeesAmount>0</totalFeesAmount><totalStampDutyAmount>0</totalStampDutyAmount><totalDeductionsAmount>0</totalDeductionsAmount><totalGrossValue>109.00</totalGrossValue>
+    <incomeClassification><classificationType>E3_561_001</classificationType><classificationCategory>category1_3</classificationCategory><amount>100.00</amount></incomeClassification></invoiceSummary>
+</invoice></InvoicesDoc>"#;
+    let book: AadeBook = from_str(xml).expect("Failed to parse XML");
+    Normalizer::normalize(book.invoices.into_iter().next().unwrap()).expect("Normalization failed")
+}
+
+fn registration(branch: i32, from: &str, until: Option<&str>) -> IslandBranch {
+    IslandBranch {
+        vat_number: "090000045".to_string(),
+        branch,
+        island: "Λέσβος".to_string(),
+        valid_from: NaiveDate::parse_from_str(from, "%Y-%m-%d").unwrap(),
+        valid_until: until.map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap()),
+    }
+}
+
+fn validate(invoice: &Invoice, registry: Option<&[IslandBranch]>) -> ValidationReport {
+    BusinessRules::validate_in_context(invoice, &ValidationContext { island_branches: registry, ..Default::default() })
+}
+
+fn has_island_error(report: &ValidationReport) -> bool {
+    report.errors.iter().any(|e| e.code == "ISL-001")
+}
+
+#[test]
+fn test_without_registry_the_rates_are_not_checked() {
+    assert!(!has_island_error(&validate(&island_invoice(), None)));
+}
+
+#[test]
+fn test_registered_branch_may_use_reduced_rates() {
+    let registry = [registration(1, "2020-01-01", None)];
+    let report = validate(&island_invoice(), Some(&registry));
+
+    // Not ISL-001 nor the per-type rate rules (VAT-003, VAT-LEGACY-001): the registry is the only gate.
+    // The INFO-001 note on island rates stays.
+    let on_category: Vec<&str> = report.errors.iter()
+        .filter(|e| e.severity != Severity::Info && e.field.as_deref() == Some("line[1].vat_category"))
+        .map(|e| e.code.as_str())
+        .collect();
+    assert!(on_category.is_empty(), "{:?}", on_category);
+    assert_ne!(report.status, ValidationStatus::Red, "{:#?}", report.errors);
+}
+
+#[test]
+fn test_island_rate_on_a_zero_rated_type_is_still_rejected() {
+    // 2.2 only allows 0% or exempt, an island registration does not change that
+    let mut invoice = island_invoice();
+    invoice.header.invoice_type = InvoiceType::ServiceInvoiceIntra;
+    let registry = [registration(1, "2020-01-01", None)];
+
+    let report = validate(&invoice, Some(&registry));
+    assert!(report.errors.iter().any(|e| e.code == "VAT-009"), "{:#?}", report.errors);
+}
+
+#[test]
+fn test_unregistered_branch_gets_the_mainland_rate() {
+    // Only the head office is on the island
+    let registry = [registration(0, "2020-01-01", None)];
+    let report = validate(&island_invoice(), Some(&registry));
+
+    let finding = report.errors.iter().find(|e| e.code == "ISL-001").expect("Missing ISL-001");
+    assert_eq!(finding.field.as_deref(), Some("line[1].vat_category"));
+    assert_eq!(finding.allowed_values, Some(vec!["2".to_string()]));
+
+    let hint = report.suggestions.iter().find(|h| h.code == "ISL-001").expect("Missing ISL-001 hint");
+    assert_eq!(hint.action, FixAction::Replace);
+    assert_eq!(hint.proposed_value.as_deref(), Some("2"));
+}
+
+#[test]
+fn test_registration_period_is_respected() {
+    let registry = [registration(1, "2019-01-01", Some("2024-12-31"))];
+    let mut report = validate(&island_invoice(), Some(&registry));
+    report.localize(Lang::En);
+
+    let finding = report.errors.iter().find(|e| e.code == "ISL-001").expect("Missing ISL-001");
+    assert!(finding.reason.contains("2024-12-31"), "{}", finding.reason);
+
+    let registry = [registration(1, "2025-02-01", None)];
+    assert!(has_island_error(&validate(&island_invoice(), Some(&registry))));
+}
+
+#[test]
+fn test_registration_is_checked_before_storing() {
+    assert_eq!(registration(1, "2024-01-01", None).registration_error(), None);
+    assert_eq!(registration(1, "2024-01-01", Some("2023-12-31")).registration_error(), Some("valid_until is before valid_from"));
+    assert_eq!(registration(-1, "2024-01-01", None).registration_error(), Some("branch cannot be negative"));
+
+    let mut unnamed = registration(1, "2024-01-01", None);
+    unnamed.island = " ".to_string();
+    assert_eq!(unnamed.registration_error(), Some("island is required"));
+}
@@ -0,0 +1,82 @@
+use aade_validator::domain::enums::InvoiceType;
+use aade_validator::domain::invoice::{Invoice, PartyRole};
+use aade_validator::validation::business_rules::BusinessRules;
+use aade_validator::validation::result::Severity;
+use aade_validator::xml::normalizer::Normalizer;
+use aade_validator::xml::parser::AadeBook;
+use quick_xml::de::from_str;
+
+fn parse(xml: &str) -> Invoice {
+    let book: AadeBook = from_str(xml).expect("Failed to parse XML");
+    Normalizer::normalize(book.invoices.into_iter().next().unwrap()).expect("Normalization failed")
+}
+
+fn document(invoice_type: &str, counterpart: Option<(&str, &str)>, header_extra: &str, summary_extra: &str) -> Invoice {
+    let counterpart = counterpart
+        .map(|(vat, country)| format!("<counterpart><vatNumber>{}</vatNumber><country>{}</country><branch>0</branch></counterpart>", vat, country))
+        .unwrap_or_default();
+    parse(&format!(r#"<InvoicesDoc><invoice>
+  <issuer><vatNumber>090000045</vatNumber><country>GR</country><branch>0</branch></issuer>
+  {}
+  <invoiceHeader><series>A</series><aa>1</aa><issueDate>2025-01-20</issueDate><invoiceType>{}</invoiceType>{}</invoiceHeader>
+  <invoiceDetails><lineNumber>1</lineNumber><netValue>100.00</netValue><vatCategory>1</vatCategory><vatAmount>24.00</vatAmount></invoiceDetails>
+  <invoiceSummary><totalNetValue>100.00</totalNetValue><totalVatAmount>24.00</totalVatAmount><totalWithheldAmount>0</totalWithheldAmount><totalFeesAmount>0</totalFeesAmount><totalStampDutyAmount>0</totalStampDutyAmount><totalDeductionsAmount>0</totalDeductionsAmount><totalGrossValue>124.00</totalGrossValue>{}</invoiceSummary>
+</invoice></InvoicesDoc>"#, counterpart, invoice_type, header_extra, summary_extra))
+}
+
+const EXPENSE: &str = "<expensesClassification><classificationType>E3_102_001</classificationType><classificationCategory>category2_1</classificationCategory><amount>100.00</amount></expensesClassification>";
+
+fn error_codes(invoice: &Invoice) -> Vec<String> {
+    BusinessRules::validate(invoice)
+        .findings(Severity::Error)
+        .map(|e| e.code.clone())
+        .collect()
+}
+
+#[test]
+fn test_sales_invoice_is_issued_by_the_seller() {
+    let invoice = document("1.1", Some(("094014201", "GR")), "", "");
+
+    assert_eq!(invoice.issuer_role(), PartyRole::Seller);
+    assert_eq!(invoice.seller().map(|p| p.field), Some("issuer"));
+    assert_eq!(invoice.buyer().map(|p| p.vat_number), Some("094014201"));
+}
+
+#[test]
+fn test_self_billing_swaps_the_roles() {
+    let invoice = document("1.1", Some(("094014201", "GR")), "<selfPricing>true</selfPricing>", "");
+
+    assert!(invoice.header.self_pricing);
+    assert_eq!(invoice.issuer_role(), PartyRole::Buyer);
+    assert_eq!(invoice.seller().map(|p| p.field), Some("counterpart"));
+    assert_eq!(invoice.buyer().map(|p| p.vat_number), Some("090000045"));
+}
+
+#[test]
+fn test_expense_record_is_classified_on_the_expense_side() {
+    let invoice = document("13.1", None, "", EXPENSE);
+    assert_eq!(invoice.header.invoice_type, InvoiceType::ExpenseRetailPurchase);
+    assert_eq!(invoice.issuer_role(), PartyRole::Buyer);
+    assert!(invoice.seller().is_none(), "Retail expenses need not name the seller");
+    assert_eq!(invoice.expense_classifications.len(), 1);
+    assert!(error_codes(&invoice).is_empty(), "{:?}", error_codes(&invoice));
+
+    let invoice = document("13.1", None, "", "");
+    let report = BusinessRules::validate(&invoice);
+    let codes: Vec<&str> = report.errors.iter().map(|e| e.code.as_str()).collect();
+    assert!(codes.contains(&"CLS-004"), "{:?}", codes);
+    let total = report.errors.iter().find(|e| e.code == "BR-CLS-TOTAL").expect("Missing BR-CLS-TOTAL");
+    assert_eq!(total.field.as_deref(), Some("invoiceSummary.expensesClassification"));
+}
+
+#[test]
+fn test_acquisition_checks_the_seller_abroad() {
+    assert!(error_codes(&document("14.1", None, "", EXPENSE)).contains(&"CP-004".to_string()));
+    assert!(error_codes(&document("14.1", Some(("094014201", "GR")), "", EXPENSE)).contains(&"CP-002".to_string()));
+
+    let invoice = document("14.1", Some(("DE136695977", "DE")), "", EXPENSE);
+    assert_eq!(invoice.seller().map(|p| p.country), Some("DE"));
+    let report = BusinessRules::validate(&invoice);
+    let finding = report.errors.iter().find(|e| e.code == "BR-VAT-EU").expect("Missing BR-VAT-EU");
+    assert_eq!(finding.field.as_deref(), Some("counterpart.vatNumber"));
+}
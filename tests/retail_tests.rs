@@ -0,0 +1,95 @@
+use aade_validator::domain::enums::PaymentMethod;
+use aade_validator::domain::invoice::{Counterpart, Invoice, PaymentDetail};
+use aade_validator::validation::business_rules::BusinessRules;
+use aade_validator::validation::result::{FixAction, Severity, ValidationStatus};
+use aade_validator::xml::normalizer::Normalizer;
+use aade_validator::xml::parser::AadeBook;
+use quick_xml::de::from_str;
+use rust_decimal_macros::dec;
+
+const RECEIPT: &str = r#"<InvoicesDoc><invoice>
+  <issuer><vatNumber>090000045</vatNumber><country>GR</country><branch>0</branch></issuer>
+  <invoiceHeader><series>POS1</series><aa>1</aa><issueDate>2025-01-20</issueDate><invoiceType>11.1</invoiceType></invoiceHeader>
+  <invoiceDetails><lineNumber>1</lineNumber><netValue>100.00</netValue><vatCategory>1</vatCategory><vatAmount>24.00</vatAmount></invoiceDetails>
+  <invoiceSummary><totalNetValue>100.00</totalNetValue><totalVatAmount>24.00</totalVatAmount><totalWithheldAmount>0</totalWithheldAmount><totalFeesAmount>0</totalFeesAmount><totalStampDutyAmount>0</totalStampDutyAmount><totalDeductionsAmount>0</totalDeductionsAmount><totalGrossValue>124.00</totalGrossValue>
+    <incomeClassification><classificationType>E3_561_003</classificationType><classificationCategory>category1_2</classificationCategory><amount>100.00</amount></incomeClassification></invoiceSummary>
+  <paymentMethods><paymentMethodDetails><type>3</type><amount>124.00</amount></paymentMethodDetails></paymentMethods>
+</invoice></InvoicesDoc>"#;
+
+fn receipt() -> Invoice {
+    let book: AadeBook = from_str(RECEIPT).expect("Failed to parse XML");
+    Normalizer::normalize(book.invoices.into_iter().next().unwrap()).expect("Normalization failed")
+}
+
+fn error_codes(invoice: &Invoice) -> Vec<String> {
+    BusinessRules::validate(invoice)
+        .findings(Severity::Error)
+        .map(|e| e.code.clone())
+        .collect()
+}
+
+#[test]
+fn test_payment_methods_are_parsed() {
+    let invoice = receipt();
+
+    assert_eq!(invoice.payment_methods.len(), 1);
+    assert_eq!(invoice.payment_methods[0].method(), Some(PaymentMethod::Cash));
+    assert_eq!(invoice.payment_methods[0].amount, dec!(124.00));
+}
+
+#[test]
+fn test_clean_receipt_only_gets_the_retail_note() {
+    let mut invoice = receipt();
+    let report = BusinessRules::validate(&invoice);
+    assert_eq!(report.status, ValidationStatus::Green, "Unexpected findings: {:?}", report.errors);
+    assert!(report.findings(Severity::Info).any(|e| e.code == "BIZ-001"));
+
+    // Other income on a receipt goes under category1_95 without an E3 type
+    let classification = &mut invoice.income_classifications[0];
+    classification.classification_type = None;
+    classification.classification_category = Some("category1_95".to_string());
+    assert!(error_codes(&invoice).is_empty(), "{:?}", error_codes(&invoice));
+}
+
+#[test]
+fn test_receipt_without_payment_methods_or_with_counterpart() {
+    let mut invoice = receipt();
+    invoice.payment_methods.clear();
+    invoice.counterpart = Some(Counterpart {
+        vat_number: "094014201".to_string(),
+        country: "GR".to_string(),
+        branch: 0,
+        name: None,
+    });
+
+    let report = BusinessRules::validate(&invoice);
+    let codes: Vec<&str> = report.errors.iter().map(|e| e.code.as_str()).collect();
+    assert!(codes.contains(&"RET-001"), "{:?}", codes);
+    assert!(codes.contains(&"RET-002"), "{:?}", codes);
+
+    let hint = report.suggestions.iter().find(|h| h.code == "RET-002").expect("Missing RET-002 hint");
+    assert_eq!(hint.action, FixAction::Add);
+    assert_eq!(hint.expected_value.as_deref(), Some("124"));
+}
+
+#[test]
+fn test_cash_ceiling_and_unknown_payment_type() {
+    let mut invoice = receipt();
+    invoice.payment_methods = vec![
+        PaymentDetail { payment_type: 3, amount: dec!(300.00), info: None },
+        PaymentDetail { payment_type: 3, amount: dec!(250.00), info: None },
+    ];
+    let report = BusinessRules::validate(&invoice);
+    let finding = report.errors.iter().find(|e| e.code == "RET-003").expect("Missing RET-003");
+    assert_eq!(finding.value_found.as_deref(), Some("550.00"));
+
+    // Card payments do not count towards the ceiling
+    invoice.payment_methods[1].payment_type = 7;
+    assert!(!error_codes(&invoice).contains(&"RET-003".to_string()));
+
+    invoice.payment_methods[1].payment_type = 99;
+    let report = BusinessRules::validate(&invoice);
+    let finding = report.errors.iter().find(|e| e.code == "BR-014").expect("Missing BR-014");
+    assert_eq!(finding.field.as_deref(), Some("paymentMethods[2].type"));
+    assert_eq!(finding.allowed_values.as_ref().map(Vec::len), Some(8));
+}
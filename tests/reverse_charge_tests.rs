@@ -0,0 +1,98 @@
+use aade_validator::domain::invoice::Invoice;
+use aade_validator::i18n::lang::Lang;
+use aade_validator::validation::business_rules::BusinessRules;
+use aade_validator::validation::result::{FixAction, Severity, ValidationReport, ValidationStatus};
+use aade_validator::validation::reverse_charge_rules::ReverseChargeScenario;
+use aade_validator::xml::normalizer::Normalizer;
+use aade_validator::xml::parser::AadeBook;
+use quick_xml::de::from_str;
+
+const SERVICES: &str = "<incomeClassification><classificationType>E3_561_005</classificationType><classificationCategory>category1_3</classificationCategory><amount>100.00</amount></incomeClassification>";
+
+// One line of 100.00 at the given category, with the exemption article if any
+fn document(invoice_type: &str, counterpart: (&str, &str), category: u8, exemption: Option<i32>) -> Invoice {
+    let vat = if category == 1 { "24.00" } else { "0.00" };
+    let gross = if category == 1 { "124.00" } else { "100.00" };
+    let exemption = exemption
+        .map(|c| format!("<vatExemptionCategory>{}</vatExemptionCategory>", c))
+        .unwrap_or_default();
+    let xml = format!(r#"<InvoicesDoc><invoice>
+  <issuer><vatNumber>090000045</vatNumber><country>GR</country><branch>0</branch></issuer>
+  <counterpart><vatNumber>{}</vatNumber><country>{}</country><branch>0</branch></counterpart>
+  <invoiceHeader><series>A</series><aa>1</aa><issueDate>2025-01-20</issueDate><invoiceType>{}</invoiceType></invoiceHeader>
+  <invoiceDetails><lineNumber>1</lineNumber><netValue>100.00</netValue><vatCategory>{}</vatCategory><vatAmount>{}</vatAmount>{}</invoiceDetails>
+  <invoiceSummary><totalNetValue>100.00</totalNetValue><totalVatAmount>{}</totalVatAmount><totalWithheldAmount>0</totalWithheldAmount><totalFeesAmount>0</totalFeesAmount><totalStampDutyAmount>0</totalStampDutyAmount><totalDeductionsAmount>0</totalDeductionsAmount><totalGrossValue>{}</totalGrossValue>
+    {}</invoiceSummary>
+</invoice></InvoicesDoc>"#, counterpart.0, counterpart.1, invoice_type, category, vat, exemption, vat, gross, SERVICES);
+    let book: AadeBook = from_str(&xml).expect("Failed to parse XML");
+    Normalizer::normalize(book.invoices.into_iter().next().unwrap()).expect("Normalization failed")
+}
+
+fn codes(report: &ValidationReport) -> Vec<&str> {
+    report.errors.iter().map(|e| e.code.as_str()).collect()
+}
+
+#[test]
+fn test_intra_eu_services_are_explained() {
+    let invoice = document("2.2", ("DE136695976", "DE"), 7, Some(4));
+    assert_eq!(ReverseChargeScenario::detect(&invoice), Some(ReverseChargeScenario::IntraEuServices));
+
+    let mut report = BusinessRules::validate(&invoice);
+    assert_eq!(report.status, ValidationStatus::Green, "{:?}", codes(&report));
+
+    report.localize(Lang::En);
+    let note = report.findings(Severity::Info).find(|e| e.code == "RC-INFO").expect("Missing RC-INFO");
+    assert!(note.reason.contains("article 14") && note.reason.contains("DE136695976"), "{}", note.reason);
+}
+
+#[test]
+fn test_zero_rated_line_needs_the_exemption_article() {
+    let report = BusinessRules::validate(&document("1.2", ("DE136695976", "DE"), 7, None));
+    let finding = report.errors.iter().find(|e| e.code == "RC-001").expect("Missing RC-001");
+    assert_eq!(finding.field.as_deref(), Some("line[1].vatExemptionCategory"));
+    assert_eq!(finding.allowed_values, Some(vec!["14".to_string()]));
+
+    let hint = report.suggestions.iter().find(|h| h.code == "RC-001").expect("Missing RC-001 hint");
+    assert_eq!(hint.action, FixAction::Add);
+    assert_eq!(hint.proposed_value.as_deref(), Some("14"));
+
+    // The goods article on a services invoice
+    let report = BusinessRules::validate(&document("2.2", ("DE136695976", "DE"), 7, Some(14)));
+    let hint = report.suggestions.iter().find(|h| h.code == "RC-001").expect("Missing RC-001 hint");
+    assert_eq!(hint.action, FixAction::Replace);
+    assert_eq!(hint.proposed_value.as_deref(), Some("4"));
+}
+
+#[test]
+fn test_services_charged_with_vat_are_rejected() {
+    let report = BusinessRules::validate(&document("2.2", ("DE136695976", "DE"), 1, None));
+    assert!(codes(&report).contains(&"VAT-009"), "{:?}", codes(&report));
+    assert!(!codes(&report).contains(&"RC-001"), "VAT-009 already covers the charged line");
+}
+
+#[test]
+fn test_domestic_reverse_charge_needs_a_greek_recipient_and_no_vat() {
+    let invoice = document("1.1", ("094014201", "GR"), 7, Some(16));
+    assert_eq!(ReverseChargeScenario::detect(&invoice), Some(ReverseChargeScenario::Domestic));
+    let report = BusinessRules::validate(&invoice);
+    assert!(report.findings(Severity::Info).any(|e| e.code == "RC-INFO"));
+    assert!(!report.findings(Severity::Error).any(|e| e.code.starts_with("RC-")), "{:?}", codes(&report));
+
+    let report = BusinessRules::validate(&document("1.1", ("094014201", "GR"), 1, Some(16)));
+    let finding = report.errors.iter().find(|e| e.code == "RC-002").expect("Missing RC-002");
+    assert_eq!(finding.field.as_deref(), Some("line[1].vat_category"));
+
+    let report = BusinessRules::validate(&document("1.1", ("DE136695976", "DE"), 7, Some(16)));
+    let finding = report.errors.iter().find(|e| e.code == "RC-003").expect("Missing RC-003");
+    assert_eq!(finding.field.as_deref(), Some("counterpart.country"));
+}
+
+#[test]
+fn test_domestic_type_to_an_eu_business_at_zero_rate_is_flagged() {
+    let report = BusinessRules::validate(&document("2.1", ("DE136695976", "DE"), 7, None));
+    let warning = report.findings(Severity::Warning).find(|e| e.code == "RC-004").expect("Missing RC-004");
+    assert_eq!(warning.field.as_deref(), Some("invoiceHeader.invoiceType"));
+
+    let hint = report.suggestions.iter().find(|h| h.code == "RC-004").expect("Missing RC-004 hint");
+    assert_eq!(hint.proposed_value.as_deref(), Some("2.2"));
+}
@@ -0,0 +1,73 @@
+use aade_validator::domain::invoice::Invoice;
+use aade_validator::validation::business_rules::BusinessRules;
+use aade_validator::validation::result::ValidationReport;
+use aade_validator::validation::revision::{PreviousAttempt, RevisionComparison};
+use aade_validator::xml::normalizer::Normalizer;
+use aade_validator::xml::parser::AadeBook;
+use chrono::Utc;
+use quick_xml::de::from_str;
+
+fn parse(path: &str) -> Invoice {
+    let xml = std::fs::read_to_string(path).expect("Failed to read sample file");
+    let book: AadeBook = from_str(&xml).expect("Failed to parse XML");
+    Normalizer::normalize(book.invoices.into_iter().next().unwrap()).expect("Normalization failed")
+}
+
+// What the validation log keeps and gives back
+fn logged(invoice: &Invoice, report: &ValidationReport) -> PreviousAttempt {
+    PreviousAttempt {
+        invoice: serde_json::from_value(serde_json::to_value(invoice).unwrap()).expect("Invoice snapshot does not round-trip"),
+        report: serde_json::from_value(serde_json::to_value(report).unwrap()).expect("Report does not round-trip"),
+        validated_at: Utc::now(),
+    }
+}
+
+#[test]
+fn test_unchanged_upload_has_nothing_to_report() {
+    let invoice = parse("tests/samples/v02_multiple_lines_e3.xml");
+    let report = BusinessRules::validate(&invoice);
+    let comparison = RevisionComparison::between(&logged(&invoice, &report), &invoice, &report);
+
+    assert!(!comparison.diff.has_changes, "{:#?}", comparison.diff.changes);
+    assert!(comparison.fixed.is_empty() && comparison.introduced.is_empty());
+}
+
+#[test]
+fn test_fixed_and_introduced_findings_are_listed() {
+    let broken = parse("tests/samples/i16_BR-001_net_mismatch_with_counterpart.xml");
+    let previous = logged(&broken, &BusinessRules::validate(&broken));
+
+    // The net total is corrected, but the counterpart's VAT number gets mistyped
+    let mut fixed = broken.clone();
+    fixed.totals.total_net_amount = fixed.lines.iter().map(|l| l.net_value).sum();
+    if let Some(cp) = fixed.counterpart.as_mut() {
+        cp.vat_number = "123456789".to_string();
+    }
+    let report = BusinessRules::validate(&fixed);
+    let comparison = RevisionComparison::between(&previous, &fixed, &report);
+
+    assert!(comparison.fixed.iter().any(|f| f.code == "BR-001"), "{:?}", comparison.fixed);
+    assert!(comparison.introduced.iter().any(|f| f.code == "BR-004"), "{:?}", comparison.introduced);
+    assert!(comparison.diff.changes.iter().any(|c| c.path == "totals.total_net_amount"));
+    assert!(comparison.diff.changes.iter().any(|c| c.path == "counterpart.vat_number"));
+}
+
+#[test]
+fn test_snapshots_logged_before_the_newer_fields_still_load() {
+    let invoice = parse("tests/samples/v02_multiple_lines_e3.xml");
+    let mut snapshot = serde_json::to_value(&invoice).unwrap();
+    for field in ["mark", "expense_classifications", "payment_methods"] {
+        snapshot.as_object_mut().unwrap().remove(field);
+    }
+    for field in ["correlated_invoices", "self_pricing"] {
+        snapshot["header"].as_object_mut().unwrap().remove(field);
+    }
+    for line in snapshot["lines"].as_array_mut().unwrap() {
+        line.as_object_mut().unwrap().remove("vat_exemption_category");
+    }
+
+    let old: Invoice = serde_json::from_value(snapshot).expect("Old snapshot does not load");
+    assert!(old.mark.is_none() && old.payment_methods.is_empty() && old.expense_classifications.is_empty());
+    assert!(old.header.correlated_invoices.is_empty() && !old.header.self_pricing);
+    assert!(old.lines.iter().all(|l| l.vat_exemption_category.is_none()));
+}
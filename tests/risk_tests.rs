@@ -0,0 +1,60 @@
+use aade_validator::domain::invoice::Invoice;
+use aade_validator::validation::business_rules::BusinessRules;
+use aade_validator::validation::risk::{IssuerHistory, RiskCategory};
+use aade_validator::xml::normalizer::Normalizer;
+use aade_validator::xml::parser::AadeBook;
+use quick_xml::de::from_str;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+fn load_sample(name: &str) -> Invoice {
+    let xml_content = fs::read_to_string(format!("tests/samples/{}", name))
+        .expect("Failed to read sample file");
+    let book: AadeBook = from_str(&xml_content).expect("Failed to parse XML");
+    Normalizer::normalize(book.invoices.into_iter().next().unwrap()).expect("Normalization failed")
+}
+
+#[test]
+fn test_clean_invoice_has_no_risk() {
+    let report = BusinessRules::validate(&load_sample("valid_invoice.xml"));
+
+    assert_eq!(report.risk_score, 0);
+    assert!(report.risk_factors.is_empty(), "Unexpected factors: {:?}", report.risk_factors);
+}
+
+#[test]
+fn test_risk_breakdown_explains_score() {
+    let report = BusinessRules::validate(&load_sample("invalid_invoice.xml"));
+
+    // BR-001/BR-002 (arithmetic) and BR-003/BR-004 (identity) each contribute once
+    let codes: Vec<&str> = report.risk_factors.iter().map(|f| f.code.as_str()).collect();
+    for code in ["BR-001", "BR-002", "BR-003", "BR-004"] {
+        assert!(codes.contains(&code), "Missing factor {} in {:?}", code, codes);
+    }
+    assert!(report.risk_factors.iter().any(|f| f.category == RiskCategory::Identity));
+
+    let total: u32 = report.risk_factors.iter().map(|f| f.points).sum();
+    assert_eq!(report.risk_score as u32, total.min(100));
+}
+
+#[test]
+fn test_history_signals() {
+    let invoice = load_sample("v02_multiple_lines_e3.xml");
+
+    // The issuer has only ever used 24% and never invoiced this counterpart
+    let history = IssuerHistory {
+        invoice_count: 20,
+        vat_category_counts: HashMap::from([("1".to_string(), 20)]),
+        known_counterparts: HashSet::from(["800000000".to_string()]),
+    };
+    let report = BusinessRules::validate_with_history(&invoice, Some(&history));
+
+    let codes: Vec<&str> = report.risk_factors.iter().map(|f| f.code.as_str()).collect();
+    assert!(codes.contains(&"RISK-VAT-UNUSUAL"), "Expected unusual VAT signal, got {:?}", codes);
+    assert!(codes.contains(&"RISK-NEW-CP"), "Expected new counterpart signal, got {:?}", codes);
+    assert!(report.risk_factors.iter().all(|f| f.category == RiskCategory::Statistical));
+    assert_eq!(report.risk_score, 25);
+
+    // Without history the same invoice carries no statistical risk
+    assert_eq!(BusinessRules::validate(&invoice).risk_score, 0);
+}
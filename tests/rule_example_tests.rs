@@ -0,0 +1,107 @@
+use aade_validator::domain::invoice::Invoice;
+use aade_validator::validation::result::{Severity, ValidationReport};
+use aade_validator::validation::rules_engine::{RuleDefinition, RuleExample, RulesEngine};
+use aade_validator::xml::normalizer::Normalizer;
+use aade_validator::xml::parser::AadeBook;
+use quick_xml::de::from_str;
+use std::fs;
+use std::path::Path;
+
+// Rules whose failing case cannot be expressed as an invoice document yet
+const RULES_WITHOUT_FAILING_EXAMPLES: &[(&str, &str)] = &[
+    ("VAT-MASTER-001", "Unknown VAT categories are rejected by the normalizer before rules run"),
+];
+
+fn load_engine() -> RulesEngine {
+    let yaml = fs::read_to_string("rules/mydata_v1.yaml").expect("Failed to read rules file");
+    RulesEngine::load_from_yaml(&yaml).expect("Failed to parse rules file")
+}
+
+fn load_invoices(rule: &RuleDefinition, example: &RuleExample) -> Vec<Invoice> {
+    let xml = example
+        .xml_content(Path::new(env!("CARGO_MANIFEST_DIR")))
+        .unwrap_or_else(|e| panic!("{}: cannot read example {}: {}", rule.id, example.label(), e));
+    let book: AadeBook = from_str(&xml)
+        .unwrap_or_else(|e| panic!("{}: example {} is not valid XML: {}", rule.id, example.label(), e));
+    book.invoices
+        .into_iter()
+        .map(|inv| {
+            Normalizer::normalize(inv)
+                .unwrap_or_else(|e| panic!("{}: example {} failed normalization: {}", rule.id, example.label(), e))
+        })
+        .collect()
+}
+
+fn fired_codes(rule: &RuleDefinition, invoice: &Invoice) -> Vec<String> {
+    let mut report = ValidationReport::new();
+    RulesEngine::apply_rule(rule, invoice, &mut report);
+    report.errors.into_iter().map(|e| e.code).collect()
+}
+
+#[test]
+fn test_failing_examples_produce_fix_hints() {
+    let engine = load_engine();
+
+    for rule in engine.rules().iter().filter(|r| r.severity != Severity::Info) {
+        for example in &rule.examples.fail {
+            for invoice in load_invoices(rule, example) {
+                let mut report = ValidationReport::new();
+                RulesEngine::apply_rule(rule, &invoice, &mut report);
+
+                // One hint per finding, pointing at the same field
+                assert_eq!(report.suggestions.len(), report.errors.len(), "{}: {:?}", rule.id, report.suggestions);
+                for (finding, hint) in report.errors.iter().zip(&report.suggestions) {
+                    assert_eq!(hint.code, rule.id);
+                    if hint.field != "incomeClassification[1].classificationType" {
+                        assert_eq!(finding.field.as_deref(), Some(hint.field.as_str()), "{}: hint field differs", rule.id);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_rule_examples_behave_as_declared() {
+    let engine = load_engine();
+    let mut failures = Vec::new();
+
+    for rule in engine.rules() {
+        for example in &rule.examples.pass {
+            for invoice in load_invoices(rule, example) {
+                let codes = fired_codes(rule, &invoice);
+                if !codes.is_empty() {
+                    failures.push(format!("{}: passing example {} reported {:?}", rule.id, example.label(), codes));
+                }
+            }
+        }
+
+        for example in &rule.examples.fail {
+            for invoice in load_invoices(rule, example) {
+                let codes = fired_codes(rule, &invoice);
+                if !codes.iter().all(|c| *c == rule.id) || codes.is_empty() {
+                    failures.push(format!("{}: failing example {} reported {:?}", rule.id, example.label(), codes));
+                }
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "Rule examples did not behave as declared:\n{}", failures.join("\n"));
+}
+
+#[test]
+fn test_every_rule_declares_examples() {
+    let engine = load_engine();
+
+    for rule in engine.rules() {
+        assert!(!rule.examples.pass.is_empty(), "{} has no passing example", rule.id);
+
+        let exempt = RULES_WITHOUT_FAILING_EXAMPLES.iter().any(|(id, _)| *id == rule.id);
+        assert_eq!(
+            rule.examples.fail.is_empty(),
+            exempt,
+            "{}: failing examples must be declared unless the rule is listed as exempt (and vice versa)",
+            rule.id
+        );
+    }
+}
@@ -1,7 +1,8 @@
 use aade_validator::xml::parser::AadeBook;
 use aade_validator::xml::normalizer::Normalizer;
 use aade_validator::validation::business_rules::BusinessRules;
-use aade_validator::validation::result::ValidationStatus;
+use aade_validator::i18n::catalog::Message;
+use aade_validator::validation::result::{FixAction, Severity, ValidationReport, ValidationStatus};
 use quick_xml::de::from_str;
 use std::fs;
 
@@ -57,3 +58,116 @@ fn test_invalid_invoice_totals_mismatch() {
     // Print errors to verify (visible with --nocapture)
     println!("Found expected errors: {:?}", report.errors);
 }
+
+#[test]
+fn test_severity_counts_drive_status() {
+    let mut report = ValidationReport::new();
+
+    report.add_info("INFO-001", Message::new("INFO-001").with("line", 1), Some("line[1].vat_category"), Some("5"));
+    assert_eq!(report.status, ValidationStatus::Green, "Info notes must not change the status");
+
+    report.add_warning("QUALITY-001", Message::new("QUALITY-001").with("line", 1), Some("line[1].vat_category"), Some("3"));
+    assert_eq!(report.status, ValidationStatus::Yellow);
+
+    report.add_error("BR-001", Message::new("BR-001"), Some("totalNetValue"), Some("120"));
+    assert_eq!(report.status, ValidationStatus::Red);
+
+    assert_eq!(report.counts.get(Severity::Info), 1);
+    assert_eq!(report.counts.get(Severity::Warning), 1);
+    assert_eq!(report.counts.get(Severity::Error), 1);
+
+    let warning = report.findings(Severity::Warning).next().unwrap();
+    assert_eq!(warning.field.as_deref(), Some("line[1].vat_category"));
+    assert_eq!(warning.value_found.as_deref(), Some("3"));
+}
+
+#[test]
+fn test_info_notes_reported_for_intra_eu_zero_rate() {
+    let xml_content = r#"<InvoicesDoc><invoice>
+  <issuer><vatNumber>090000045</vatNumber><country>GR</country><branch>0</branch></issuer>
+  <counterpart><vatNumber>DE136695976</vatNumber><country>DE</country><branch>0</branch></counterpart>
+  <invoiceHeader><series>A</series><aa>1</aa><issueDate>2025-01-20</issueDate><invoiceType>1.2</invoiceType></invoiceHeader>
+  <invoiceDetails><lineNumber>1</lineNumber><netValue>100.00</netValue><vatCategory>7</vatCategory><vatAmount>0.00</vatAmount><vatExemptionCategory>14</vatExemptionCategory></invoiceDetails>
+  <invoiceSummary><totalNetValue>100.00</totalNetValue><totalVatAmount>0.00</totalVatAmount><totalWithheldAmount>0</totalWithheldAmount><totalFeesAmount>0</totalFeesAmount><totalStampDutyAmount>0</totalStampDutyAmount><totalDeductionsAmount>0</totalDeductionsAmount><totalGrossValue>100.00</totalGrossValue>
+    <incomeClassification><classificationType>E3_561_005</classificationType><classificationCategory>category1_1</classificationCategory><amount>100.00</amount></incomeClassification></invoiceSummary>
+</invoice></InvoicesDoc>"#;
+
+    let book: AadeBook = from_str(xml_content).expect("Failed to parse XML");
+    let invoice = Normalizer::normalize(book.invoices.into_iter().next().unwrap()).expect("Normalization failed");
+    let report = BusinessRules::validate(&invoice);
+
+    assert_eq!(report.status, ValidationStatus::Green, "Unexpected findings: {:?}", report.errors);
+    assert!(report.findings(Severity::Info).any(|e| e.code == "INFO-002"), "Missing reverse-charge note");
+}
+
+#[test]
+fn test_fix_hints_carry_expected_and_allowed_values() {
+    let xml_content = r#"<InvoicesDoc><invoice>
+  <issuer><vatNumber>090000045</vatNumber><country>GR</country><branch>0</branch></issuer>
+  <counterpart><vatNumber>DE136695976</vatNumber><country>DE</country><branch>0</branch></counterpart>
+  <invoiceHeader><series>A</series><aa>1</aa><issueDate>2025-01-20</issueDate><invoiceType>1.2</invoiceType></invoiceHeader>
+  <invoiceDetails><lineNumber>1</lineNumber><netValue>100.00</netValue><vatCategory>1</vatCategory><vatAmount>24.00</vatAmount></invoiceDetails>
+  <invoiceSummary><totalNetValue>90.00</totalNetValue><totalVatAmount>24.00</totalVatAmount><totalWithheldAmount>0</totalWithheldAmount><totalFeesAmount>0</totalFeesAmount><totalStampDutyAmount>0</totalStampDutyAmount><totalDeductionsAmount>0</totalDeductionsAmount><totalGrossValue>114.00</totalGrossValue>
+    <incomeClassification><classificationType>E3_561_005</classificationType><classificationCategory>category1_1</classificationCategory><amount>90.00</amount></incomeClassification></invoiceSummary>
+</invoice></InvoicesDoc>"#;
+
+    let book: AadeBook = from_str(xml_content).expect("Failed to parse XML");
+    let invoice = Normalizer::normalize(book.invoices.into_iter().next().unwrap()).expect("Normalization failed");
+    let report = BusinessRules::validate(&invoice);
+
+    // Header rule: allowed set on both the finding and the hint
+    let vat_error = report.errors.iter().find(|e| e.code == "VAT-002").expect("Missing VAT-002");
+    assert_eq!(vat_error.allowed_values, Some(vec!["7".to_string(), "8".to_string()]));
+    let vat_hint = report.suggestions.iter().find(|h| h.code == "VAT-002").expect("Missing VAT-002 hint");
+    assert_eq!(vat_hint.field, "line[1].vat_category");
+    assert_eq!(vat_hint.action, FixAction::Replace);
+    assert_eq!(vat_hint.proposed_value, None, "Two values are allowed, neither is proposed");
+
+    // Arithmetic rule: recomputed amount
+    let net_hint = report.suggestions.iter().find(|h| h.code == "BR-001").expect("Missing BR-001 hint");
+    assert_eq!(net_hint.field, "totalNetValue");
+    assert_eq!(net_hint.expected_value.as_deref(), Some("100"));
+    assert_eq!(net_hint.proposed_value.as_deref(), Some("100"));
+}
+
+#[test]
+fn test_future_date_hint_proposes_nothing() {
+    let xml_content = fs::read_to_string("tests/samples/i20_BR-005_future_date_with_counterpart.xml")
+        .expect("Failed to read sample file");
+    let book: AadeBook = from_str(&xml_content).expect("Failed to parse XML");
+    let invoice = Normalizer::normalize(book.invoices.into_iter().next().unwrap()).expect("Normalization failed");
+    let report = BusinessRules::validate(&invoice);
+
+    // Today's date would be a guess, the issuer has to check the real one
+    let hint = report.suggestions.iter().find(|h| h.code == "BR-005").expect("Missing BR-005 hint");
+    assert_eq!(hint.action, FixAction::Review);
+    assert_eq!(hint.proposed_value, None);
+}
+
+#[test]
+fn test_eu_counterpart_vat_number_is_checked() {
+    let xml_content = fs::read_to_string("tests/rule_examples/intra_eu_sale_de.xml")
+        .expect("Failed to read sample file");
+    let book: AadeBook = from_str(&xml_content).expect("Failed to parse XML");
+    let mut invoice = Normalizer::normalize(book.invoices.into_iter().next().unwrap()).expect("Normalization failed");
+
+    // DE136695976 passes the German check digit
+    assert!(!BusinessRules::validate(&invoice).errors.iter().any(|e| e.code == "BR-VAT-EU"));
+
+    // The explanation names the national rule that failed
+    let counterpart = invoice.counterpart.as_mut().unwrap();
+    counterpart.vat_number = "DE136695977".to_string();
+    let mut report = BusinessRules::validate(&invoice);
+    report.localize(aade_validator::i18n::lang::Lang::En);
+    let finding = report.errors.iter().find(|e| e.code == "BR-VAT-EU").expect("Missing BR-VAT-EU");
+    assert_eq!(finding.field.as_deref(), Some("counterpart.vatNumber"));
+    assert!(finding.reason.contains("ISO 7064 MOD 11,10"), "{}", finding.reason);
+
+    let counterpart = invoice.counterpart.as_mut().unwrap();
+    counterpart.country = "AT".to_string();
+    counterpart.vat_number = "ATU1358562".to_string();
+    let mut report = BusinessRules::validate(&invoice);
+    report.localize(aade_validator::i18n::lang::Lang::En);
+    let finding = report.errors.iter().find(|e| e.code == "BR-VAT-EU").expect("Missing BR-VAT-EU");
+    assert!(finding.reason.contains("U99999999"), "{}", finding.reason);
+}